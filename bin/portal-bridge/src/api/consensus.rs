@@ -1,8 +1,11 @@
 use std::fmt::Display;
 
 use anyhow::anyhow;
+use ethportal_api::consensus::historical_summaries::HistoricalSummaries;
+use serde_json::Value;
 use tokio::time::sleep;
 use tracing::{debug, warn};
+use trin_validation::anchor_provider::{AnchorError, AnchorProvider};
 use url::Url;
 
 use crate::{
@@ -125,6 +128,19 @@ impl ConsensusApi {
     }
 }
 
+impl AnchorProvider for ConsensusApi {
+    async fn historical_summaries(&self) -> Result<HistoricalSummaries, AnchorError> {
+        let beacon_state = self
+            .get_beacon_state()
+            .await
+            .map_err(|err| AnchorError::Request(err.to_string()))?;
+        let beacon_state: Value = serde_json::from_str(&beacon_state)
+            .map_err(|err| AnchorError::InvalidResponse(err.to_string()))?;
+        serde_json::from_value(beacon_state["data"]["historical_summaries"].clone())
+            .map_err(|err| AnchorError::InvalidResponse(err.to_string()))
+    }
+}
+
 /// Check that provider is valid and accessible.
 async fn check_provider(client: &ClientWithBaseUrl) -> anyhow::Result<()> {
     let endpoint = "/eth/v1/node/version".to_string();
@@ -135,3 +151,70 @@ async fn check_provider(client: &ClientWithBaseUrl) -> anyhow::Result<()> {
         )),
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use alloy::primitives::B256;
+    use ethportal_api::consensus::historical_summaries::HistoricalSummary;
+    use serde_json::json;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    /// Starts a mocked Beacon API server that responds to every request with the given body.
+    async fn mock_beacon_api(body: String) -> Url {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = Url::parse(&format!("http://{}", listener.local_addr().unwrap())).unwrap();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = [0u8; 4096];
+                let _ = stream.read(&mut request).await.unwrap();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn historical_summaries_from_beacon_state() {
+        let historical_summaries: HistoricalSummaries = vec![
+            HistoricalSummary {
+                block_summary_root: B256::random(),
+                state_summary_root: B256::random(),
+            };
+            3
+        ]
+        .into();
+        let body = json!({
+            "version": "deneb",
+            "data": { "historical_summaries": historical_summaries },
+        });
+        let url = mock_beacon_api(body.to_string()).await;
+        let consensus_api = ConsensusApi::new(url.clone(), url, 5).await.unwrap();
+
+        assert_eq!(
+            consensus_api.historical_summaries().await.unwrap(),
+            historical_summaries
+        );
+    }
+
+    #[tokio::test]
+    async fn historical_summaries_from_invalid_response() {
+        let url = mock_beacon_api(json!({ "data": {} }).to_string()).await;
+        let consensus_api = ConsensusApi::new(url.clone(), url, 5).await.unwrap();
+
+        assert!(matches!(
+            consensus_api.historical_summaries().await,
+            Err(AnchorError::InvalidResponse(_))
+        ));
+    }
+}
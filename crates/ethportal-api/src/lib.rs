@@ -11,8 +11,8 @@ pub mod discv5;
 mod eth;
 mod history;
 mod state;
-#[cfg(test)]
-mod test_utils;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
 pub mod types;
 pub mod utils;
 pub mod version;
@@ -3,7 +3,16 @@ use std::{
     path::{Path, PathBuf},
 };
 
+use alloy::primitives::B256;
+use ssz_types::VariableList;
+
 use self::constants::PORTAL_SPEC_TESTS_SUBMODULE_PATH;
+use crate::types::consensus::{
+    beacon_block::{BeaconBlockBellatrix, BeaconBlockCapella, BeaconBlockDeneb, SignedBeaconBlock},
+    body::{BeaconBlockBodyCapella, BeaconBlockBodyDeneb},
+    execution_payload::ExecutionPayloadDeneb,
+    fork::ForkName,
+};
 
 pub mod constants;
 
@@ -16,3 +25,97 @@ pub fn read_file_from_tests_submodule<P: AsRef<Path>>(path: P) -> io::Result<Str
 pub fn read_bytes_from_tests_submodule<P: AsRef<Path>>(path: P) -> io::Result<Vec<u8>> {
     fs::read(PathBuf::from(PORTAL_SPEC_TESTS_SUBMODULE_PATH).join(path))
 }
+
+/// Reads all files with the given extension from a directory of the "portal-spec-tests"
+/// submodule, sorted by file name.
+///
+/// Returns the file name and the content of each file.
+pub fn read_dir_from_tests_submodule<P: AsRef<Path>>(
+    path: P,
+    extension: &str,
+) -> io::Result<Vec<(String, String)>> {
+    let mut files = vec![];
+    for entry in fs::read_dir(PathBuf::from(PORTAL_SPEC_TESTS_SUBMODULE_PATH).join(path))? {
+        let path = entry?.path();
+        if !path.is_file() || path.extension().is_none_or(|ext| ext != extension) {
+            continue;
+        }
+        let file_name = path
+            .file_name()
+            .and_then(|file_name| file_name.to_str())
+            .ok_or_else(|| io::Error::other(format!("invalid file name: {path:?}")))?
+            .to_string();
+        files.push((file_name, fs::read_to_string(&path)?));
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Reads the Bellatrix beacon block of the execution block 15537397 from the test assets.
+pub fn bellatrix_block() -> BeaconBlockBellatrix {
+    let raw_block = fs::read(
+        "../../test_assets/beacon/bellatrix/ValidSignedBeaconBlock/signed_beacon_block_15537397.ssz",
+    )
+    .expect("cannot read the Bellatrix beacon block from the test assets");
+    SignedBeaconBlock::from_ssz_bytes(&raw_block, ForkName::Bellatrix)
+        .expect("cannot decode the Bellatrix beacon block")
+        .message_merge()
+        .expect("the beacon block should be a Bellatrix block")
+        .clone()
+}
+
+/// Builds a Capella beacon block at the given slot, as there is no Capella block in the test
+/// assets.
+pub fn capella_block(slot: u64) -> BeaconBlockCapella {
+    let block = bellatrix_block();
+    let body = block.body;
+    BeaconBlockCapella {
+        slot,
+        proposer_index: block.proposer_index,
+        parent_root: block.parent_root,
+        state_root: block.state_root,
+        body: BeaconBlockBodyCapella {
+            randao_reveal: body.randao_reveal,
+            eth1_data: body.eth1_data,
+            graffiti: body.graffiti,
+            proposer_slashings: body.proposer_slashings,
+            attester_slashings: body.attester_slashings,
+            attestations: body.attestations,
+            deposits: body.deposits,
+            voluntary_exits: body.voluntary_exits,
+            sync_aggregate: body.sync_aggregate,
+            execution_payload: Default::default(),
+            bls_to_execution_changes: VariableList::empty(),
+        },
+    }
+}
+
+/// Builds a Deneb beacon block at the given slot, with the execution payload of the given block
+/// hash, as there is no Deneb block in the test assets.
+pub fn deneb_block(slot: u64, block_hash: B256) -> BeaconBlockDeneb {
+    let block = bellatrix_block();
+    let body = block.body;
+    BeaconBlockDeneb {
+        slot,
+        proposer_index: block.proposer_index,
+        parent_root: block.parent_root,
+        state_root: block.state_root,
+        body: BeaconBlockBodyDeneb {
+            randao_reveal: body.randao_reveal,
+            eth1_data: body.eth1_data,
+            graffiti: body.graffiti,
+            proposer_slashings: body.proposer_slashings,
+            attester_slashings: body.attester_slashings,
+            attestations: body.attestations,
+            deposits: body.deposits,
+            voluntary_exits: body.voluntary_exits,
+            sync_aggregate: body.sync_aggregate,
+            execution_payload: ExecutionPayloadDeneb {
+                block_hash,
+                ..Default::default()
+            },
+            bls_to_execution_changes: VariableList::empty(),
+            blob_kzg_commitments: VariableList::empty(),
+        },
+    }
+}
@@ -1,5 +1,6 @@
-use std::{fmt, hash::Hash};
+use std::{collections::HashMap, fmt, hash::Hash};
 
+use alloy::primitives::B256;
 use bytes::{BufMut, BytesMut};
 use rand::{seq::SliceRandom, RngCore};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
@@ -130,6 +131,37 @@ impl<'de> Deserialize<'de> for HistoryContentKey {
     }
 }
 
+/// Resolves block numbers to the hashes of the canonical blocks.
+pub trait BlockIdResolver {
+    /// Returns the hash of the canonical block with the given number, if it's known.
+    fn block_hash(&self, block_number: u64) -> Option<B256>;
+}
+
+impl BlockIdResolver for HashMap<u64, B256> {
+    fn block_hash(&self, block_number: u64) -> Option<B256> {
+        self.get(&block_number).copied()
+    }
+}
+
+/// Converts a header by number key to the equivalent header by hash key, if the hash of the block
+/// is known, so that the same header isn't stored twice under different keys.
+///
+/// Other keys are returned unchanged.
+pub fn normalize_history_key(
+    key: &HistoryContentKey,
+    resolver: &impl BlockIdResolver,
+) -> HistoryContentKey {
+    match key {
+        HistoryContentKey::BlockHeaderByNumber(BlockHeaderByNumberKey { block_number }) => {
+            match resolver.block_hash(*block_number) {
+                Some(block_hash) => HistoryContentKey::new_block_header_by_hash(block_hash),
+                None => key.clone(),
+            }
+        }
+        _ => key.clone(),
+    }
+}
+
 /// A key for a block header by hash.
 #[derive(Clone, Debug, Decode, Encode, Eq, PartialEq, Default)]
 pub struct BlockHeaderByHashKey {
@@ -299,7 +331,7 @@ impl OverlayContentKey for HistoryContentKey {
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod test {
-    use alloy::{hex::FromHex, primitives::B256};
+    use alloy::hex::FromHex;
 
     use super::*;
     use crate::{types::content_key::overlay::OverlayContentKey, utils::bytes::hex_decode};
@@ -568,4 +600,23 @@ mod test {
                 .unwrap()
         );
     }
+
+    #[test]
+    fn normalize_header_by_number_key() {
+        let resolver = HashMap::from([(1_000_001, B256::from(BLOCK_HASH))]);
+        let by_hash_key = HistoryContentKey::new_block_header_by_hash(BLOCK_HASH);
+        let by_number_key = HistoryContentKey::new_block_header_by_number(1_000_001);
+
+        assert_eq!(
+            normalize_history_key(&by_number_key, &resolver),
+            by_hash_key
+        );
+        assert_eq!(normalize_history_key(&by_hash_key, &resolver), by_hash_key);
+
+        // Unknown block numbers and other keys are unchanged
+        let unknown_key = HistoryContentKey::new_block_header_by_number(1_000_002);
+        assert_eq!(normalize_history_key(&unknown_key, &resolver), unknown_key);
+        let body_key = HistoryContentKey::new_block_body(BLOCK_HASH);
+        assert_eq!(normalize_history_key(&body_key, &resolver), body_key);
+    }
 }
@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+
+use alloy::primitives::B256;
+
+/// Caches the proofs that beacon blocks are part of the historical batch of their era, keyed by
+/// the slot and root of the beacon block.
+///
+/// The `beacon_block_proof` of a beacon block doesn't depend on the execution block, so it can be
+/// reused when the proofs of the same slot are generated again, see the `*_with_cache` builders
+/// in [header_with_proof](super::header_with_proof).
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BeaconBlockProofCache {
+    proofs: HashMap<(u64, B256), Vec<B256>>,
+}
+
+impl BeaconBlockProofCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the cached proof of the beacon block, if any.
+    pub fn get(&self, slot: u64, beacon_block_root: B256) -> Option<&[B256]> {
+        self.proofs
+            .get(&(slot, beacon_block_root))
+            .map(Vec::as_slice)
+    }
+
+    /// Returns the cached proof of the beacon block, building and caching it if it's missing.
+    pub fn get_or_insert_with(
+        &mut self,
+        slot: u64,
+        beacon_block_root: B256,
+        build_proof: impl FnOnce() -> Vec<B256>,
+    ) -> &[B256] {
+        self.proofs
+            .entry((slot, beacon_block_root))
+            .or_insert_with(build_proof)
+    }
+
+    pub fn len(&self) -> usize {
+        self.proofs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.proofs.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+
+    #[test]
+    fn cache_hit_skips_building_proof() {
+        let mut cache = BeaconBlockProofCache::new();
+        let beacon_block_root = B256::random();
+        let builds = Cell::new(0);
+        let build_proof = || {
+            builds.set(builds.get() + 1);
+            (0..13).map(|_| B256::random()).collect()
+        };
+
+        let proof = cache
+            .get_or_insert_with(6_209_538, beacon_block_root, build_proof)
+            .to_vec();
+        assert_eq!(builds.get(), 1);
+        assert_eq!(proof.len(), 13);
+
+        let cached_proof = cache.get_or_insert_with(6_209_538, beacon_block_root, build_proof);
+        assert_eq!(builds.get(), 1);
+        assert_eq!(cached_proof, proof);
+        assert_eq!(
+            cache.get(6_209_538, beacon_block_root),
+            Some(proof.as_slice())
+        );
+    }
+
+    #[test]
+    fn cache_miss_for_other_beacon_block() {
+        let mut cache = BeaconBlockProofCache::new();
+        let beacon_block_root = B256::random();
+        cache.get_or_insert_with(6_209_538, beacon_block_root, || vec![B256::random(); 13]);
+
+        assert_eq!(cache.get(6_209_539, beacon_block_root), None);
+        assert_eq!(cache.get(6_209_538, B256::random()), None);
+        assert_eq!(cache.len(), 1);
+    }
+}
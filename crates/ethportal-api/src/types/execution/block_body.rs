@@ -16,9 +16,13 @@ use anyhow::{anyhow, bail};
 use serde::Deserialize;
 use ssz::{Encode, SszDecoderBuilder, SszEncoder};
 
+pub const PRAGUE_TIMESTAMP: u64 = 1746612311;
+pub const CANCUN_TIMESTAMP: u64 = 1710338135;
 pub const SHANGHAI_TIMESTAMP: u64 = 1681338455;
 // block 15537393 timestamp
 pub const MERGE_TIMESTAMP: u64 = 1663224162;
+// block 12965000 timestamp
+pub const LONDON_TIMESTAMP: u64 = 1628166822;
 
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize, RlpEncodableWrapper, RlpDecodableWrapper)]
 pub struct BlockBody(pub AlloyBlockBody<TxEnvelope>);
@@ -0,0 +1,2115 @@
+use alloy::{
+    consensus::{Header, EMPTY_ROOT_HASH},
+    eips::{
+        eip1559::{BaseFeeParams, INITIAL_BASE_FEE},
+        eip4844::DATA_GAS_PER_BLOB,
+        eip7840::BlobParams,
+    },
+    primitives::{Address, Bloom, BloomInput, Bytes, B256, B64, U256},
+    rlp::{Decodable, Encodable},
+};
+use thiserror::Error;
+
+use super::block_body::{CANCUN_TIMESTAMP, LONDON_TIMESTAMP, PRAGUE_TIMESTAMP, SHANGHAI_TIMESTAMP};
+use crate::{
+    types::consensus::{
+        execution_payload::{
+            ExecutionPayloadHeader, ExecutionPayloadHeaderBellatrix, ExecutionPayloadHeaderCapella,
+            ExecutionPayloadHeaderDeneb, ExtraData,
+        },
+        fork::ForkName,
+    },
+    utils::bytes::hex_encode,
+};
+
+/// Number of the first London block, on mainnet.
+pub const LONDON_BLOCK_NUMBER: u64 = 12_965_000;
+
+/// Number of the first post-merge block, on mainnet.
+pub const MERGE_BLOCK_NUMBER: u64 = 15_537_394;
+
+/// Number of the first Byzantium block, on mainnet.
+pub const BYZANTIUM_BLOCK_NUMBER: u64 = 4_370_000;
+
+/// Number of the first Constantinople block, on mainnet.
+pub const CONSTANTINOPLE_BLOCK_NUMBER: u64 = 7_280_000;
+
+/// Base block reward of pre-Byzantium blocks, in wei.
+const FRONTIER_BLOCK_REWARD: u128 = 5_000_000_000_000_000_000;
+
+/// Base block reward of Byzantium blocks (EIP-649), in wei.
+const BYZANTIUM_BLOCK_REWARD: u128 = 3_000_000_000_000_000_000;
+
+/// Base block reward from Constantinople (EIP-1234) until the merge, in wei.
+const CONSTANTINOPLE_BLOCK_REWARD: u128 = 2_000_000_000_000_000_000;
+
+/// Activation blocks of the forks that delayed the difficulty bomb, on mainnet, with the delay
+/// (in blocks) that each of them introduced.
+const DIFFICULTY_BOMB_DELAYS: [(u64, u64); 6] = [
+    // Byzantium (EIP-649)
+    (BYZANTIUM_BLOCK_NUMBER, 3_000_000),
+    // Constantinople (EIP-1234)
+    (CONSTANTINOPLE_BLOCK_NUMBER, 5_000_000),
+    // Muir Glacier (EIP-2384)
+    (9_200_000, 9_000_000),
+    // London (EIP-3554)
+    (LONDON_BLOCK_NUMBER, 9_700_000),
+    // Arrow Glacier (EIP-4345)
+    (13_773_000, 10_700_000),
+    // Gray Glacier (EIP-5133)
+    (15_050_000, 11_400_000),
+];
+
+/// Genesis time of the beacon chain, on mainnet.
+const MAINNET_BEACON_GENESIS_TIME: u64 = 1_606_824_023;
+
+/// Duration of a beacon chain slot, on mainnet.
+const MAINNET_SECONDS_PER_SLOT: u64 = 12;
+
+/// The forks that changed the fields of the header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HeaderFork {
+    /// Pre-London header, without any of the optional fields.
+    Frontier,
+    /// Adds `base_fee_per_gas`.
+    London,
+    /// Adds `withdrawals_root`.
+    Shanghai,
+    /// Adds `blob_gas_used`, `excess_blob_gas` and `parent_beacon_block_root`.
+    Cancun,
+    /// Adds `requests_hash`.
+    Prague,
+}
+
+impl HeaderFork {
+    /// Returns the fork implied by the fields present in the header.
+    pub fn from_fields(header: &Header) -> Self {
+        if header.requests_hash.is_some() {
+            Self::Prague
+        } else if header.parent_beacon_block_root.is_some() {
+            Self::Cancun
+        } else if header.withdrawals_root.is_some() {
+            Self::Shanghai
+        } else if header.base_fee_per_gas.is_some() {
+            Self::London
+        } else {
+            Self::Frontier
+        }
+    }
+
+    /// Returns the fork that is active at the given timestamp, on mainnet.
+    pub fn from_timestamp(timestamp: u64) -> Self {
+        if timestamp >= PRAGUE_TIMESTAMP {
+            Self::Prague
+        } else if timestamp >= CANCUN_TIMESTAMP {
+            Self::Cancun
+        } else if timestamp >= SHANGHAI_TIMESTAMP {
+            Self::Shanghai
+        } else if timestamp >= LONDON_TIMESTAMP {
+            Self::London
+        } else {
+            Self::Frontier
+        }
+    }
+}
+
+/// The execution layer networks with known fork activation timestamps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KnownNetwork {
+    Mainnet,
+    Sepolia,
+    Holesky,
+}
+
+impl KnownNetwork {
+    /// Returns the activation timestamps of the forks of the network, starting with the latest.
+    ///
+    /// Block-activated forks are listed with the timestamp of their first block.
+    fn fork_timestamps(&self) -> &'static [(u64, HeaderFork)] {
+        match self {
+            Self::Mainnet => &[
+                (PRAGUE_TIMESTAMP, HeaderFork::Prague),
+                (CANCUN_TIMESTAMP, HeaderFork::Cancun),
+                (SHANGHAI_TIMESTAMP, HeaderFork::Shanghai),
+                (LONDON_TIMESTAMP, HeaderFork::London),
+            ],
+            // London is active from genesis
+            Self::Sepolia => &[
+                (1_741_159_776, HeaderFork::Prague),
+                (1_706_655_072, HeaderFork::Cancun),
+                (1_677_557_088, HeaderFork::Shanghai),
+                (0, HeaderFork::London),
+            ],
+            // London is active from genesis
+            Self::Holesky => &[
+                (1_740_434_112, HeaderFork::Prague),
+                (1_707_305_664, HeaderFork::Cancun),
+                (1_696_000_704, HeaderFork::Shanghai),
+                (0, HeaderFork::London),
+            ],
+        }
+    }
+
+    /// Returns the genesis time of the beacon chain of the network.
+    pub fn beacon_genesis_time(&self) -> u64 {
+        match self {
+            Self::Mainnet => MAINNET_BEACON_GENESIS_TIME,
+            Self::Sepolia => 1_655_733_600,
+            Self::Holesky => 1_695_902_400,
+        }
+    }
+}
+
+/// Returns the fork that is active at the given timestamp on the network.
+///
+/// Unlike [HeaderFork::from_timestamp], which only knows the mainnet forks, this uses the fork
+/// activation timestamps of the given network.
+///
+/// This returns a [HeaderFork] rather than a [ForkName], because [ForkName] only names the
+/// consensus forks from Bellatrix to Deneb, so it can't represent the timestamps before the merge
+/// nor after Prague, which the header layout depends on.
+pub fn fork_at_timestamp(timestamp: u64, network: KnownNetwork) -> HeaderFork {
+    network
+        .fork_timestamps()
+        .iter()
+        .find(|(fork_timestamp, _)| timestamp >= *fork_timestamp)
+        .map_or(HeaderFork::Frontier, |(_, fork)| *fork)
+}
+
+/// The fork of a decoded header, as implied by its fields and by its timestamp.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DetectedFork {
+    /// The fork implied by the fields present in the header.
+    pub fields_fork: HeaderFork,
+    /// The fork implied by the header's timestamp.
+    pub timestamp_fork: HeaderFork,
+}
+
+impl DetectedFork {
+    /// Returns whether the fields present in the header match the fork active at its timestamp.
+    pub fn is_consistent(&self) -> bool {
+        self.fields_fork == self.timestamp_fork
+    }
+}
+
+/// Checks whether the header's `parent_beacon_block_root` matches the given beacon block root.
+///
+/// Returns `None` if the header doesn't have the `parent_beacon_block_root` (pre-Cancun header).
+pub fn header_parent_beacon_root_matches(header: &Header, beacon_block_root: B256) -> Option<bool> {
+    header
+        .parent_beacon_block_root
+        .map(|parent_beacon_block_root| parent_beacon_block_root == beacon_block_root)
+}
+
+/// Returns the index in the ring buffers of the beacon roots contract (EIP-4788) where the
+/// `parent_beacon_block_root` of the header with the given timestamp is stored.
+///
+/// The timestamp is stored in the storage slot at the index, and the root in the storage slot at
+/// the index plus [BEACON_ROOTS_HISTORY_BUFFER_LENGTH].
+pub fn beacon_roots_ring_index(timestamp: u64) -> u64 {
+    timestamp % BEACON_ROOTS_HISTORY_BUFFER_LENGTH
+}
+
+/// Returns whether the block has withdrawals, i.e. whether the header's `withdrawals_root` is
+/// present and not the root of an empty trie.
+///
+/// This allows skipping the decoding and verification of an empty withdrawals list.
+pub fn expects_withdrawals(header: &Header) -> bool {
+    header
+        .withdrawals_root
+        .is_some_and(|withdrawals_root| withdrawals_root != EMPTY_ROOT_HASH)
+}
+
+/// Returns whether the block has transactions, i.e. whether the header's `transactions_root` is
+/// not the root of an empty trie.
+///
+/// This is the closest hint of the transactions count available from the header alone, the exact
+/// count requires the block body.
+pub fn has_transactions(header: &Header) -> bool {
+    header.transactions_root != EMPTY_ROOT_HASH
+}
+
+/// Length of the ring buffers of the beacon roots contract (EIP-4788).
+pub const BEACON_ROOTS_HISTORY_BUFFER_LENGTH: u64 = 8191;
+
+/// Index of the `extra_data` field in the RLP list of the header fields.
+const EXTRA_DATA_FIELD_INDEX: usize = 12;
+
+/// Returns the beacon slot that the execution block with the given timestamp was proposed in.
+///
+/// Timestamps before the genesis time are mapped to the genesis slot.
+pub fn slot_from_timestamp(timestamp: u64, genesis_time: u64, seconds_per_slot: u64) -> u64 {
+    timestamp.saturating_sub(genesis_time) / seconds_per_slot
+}
+
+/// Returns how many beacon slots old the block is at the current slot, negative if the block's
+/// timestamp is after the current slot.
+pub fn age_in_slots(
+    header: &Header,
+    current_slot: u64,
+    genesis_time: u64,
+    seconds_per_slot: u64,
+) -> i64 {
+    let slot = slot_from_timestamp(header.timestamp, genesis_time, seconds_per_slot);
+    current_slot as i64 - slot as i64
+}
+
+/// Returns the mainnet beacon slot that the execution block with the given timestamp was proposed
+/// in.
+pub fn mainnet_slot_from_timestamp(timestamp: u64) -> u64 {
+    slot_from_timestamp(
+        timestamp,
+        MAINNET_BEACON_GENESIS_TIME,
+        MAINNET_SECONDS_PER_SLOT,
+    )
+}
+
+/// Returns the timestamp of the mainnet beacon slot, which is the timestamp of the execution block
+/// proposed in it.
+pub fn mainnet_timestamp_at_slot(slot: u64) -> u64 {
+    timestamp_at_slot(slot, KnownNetwork::Mainnet)
+}
+
+/// Returns the timestamp of the beacon slot on the network, which is the timestamp of the
+/// execution block proposed in it.
+///
+/// All the known networks have the same slot duration as mainnet.
+pub fn timestamp_at_slot(slot: u64, network: KnownNetwork) -> u64 {
+    network
+        .beacon_genesis_time()
+        .saturating_add(slot.saturating_mul(MAINNET_SECONDS_PER_SLOT))
+}
+
+/// A hash function used to compute the hash of headers, for chains that don't use keccak256.
+#[cfg(feature = "custom-header-hash")]
+pub trait HashFn {
+    fn hash(&self, data: &[u8]) -> B256;
+}
+
+/// The keccak256 hash function, used to compute the hash of mainnet headers.
+#[cfg(feature = "custom-header-hash")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Keccak256;
+
+#[cfg(feature = "custom-header-hash")]
+impl HashFn for Keccak256 {
+    fn hash(&self, data: &[u8]) -> B256 {
+        alloy::primitives::keccak256(data)
+    }
+}
+
+/// Returns the hash of the RLP encoded header, computed with the given hash function.
+///
+/// With [Keccak256], this is the same as `Header::hash_slow`.
+#[cfg(feature = "custom-header-hash")]
+pub fn header_hash_with(header: &Header, hasher: &impl HashFn) -> B256 {
+    hasher.hash(&alloy::rlp::encode(header))
+}
+
+/// An error decoding a header from an Erigon header snapshot record.
+#[cfg(feature = "erigon-interop")]
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ErigonSnapshotError {
+    /// The record is empty, so it doesn't have the first byte of the block hash.
+    #[error("empty Erigon header snapshot record")]
+    EmptyRecord,
+    /// The record doesn't contain a valid RLP encoded header.
+    #[error("invalid RLP encoded header: {0}")]
+    InvalidRlp(#[from] alloy::rlp::Error),
+    /// The first byte of the record doesn't match the first byte of the header's hash.
+    #[error("record has hash prefix {record_prefix:#04x}, but the header hash starts with {hash_prefix:#04x}")]
+    HashPrefixMismatch { record_prefix: u8, hash_prefix: u8 },
+}
+
+/// Encodes the header as a record of Erigon's header snapshots (`headers.seg`), i.e. the first
+/// byte of the block hash followed by the RLP encoded header.
+///
+/// This is the layout of a single record before the segment compression, which is left to the
+/// tools that read and write the segment files.
+#[cfg(feature = "erigon-interop")]
+pub fn header_to_erigon_snapshot_bytes(header: &Header) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(1 + header.length());
+    bytes.push(header.hash_slow()[0]);
+    header.encode(&mut bytes);
+    bytes
+}
+
+/// Decodes the header from a record of Erigon's header snapshots, checking that the first byte
+/// of the record matches the hash of the decoded header.
+#[cfg(feature = "erigon-interop")]
+pub fn header_from_erigon_snapshot_bytes(bytes: &[u8]) -> Result<Header, ErigonSnapshotError> {
+    let (record_prefix, raw_header) = bytes
+        .split_first()
+        .ok_or(ErigonSnapshotError::EmptyRecord)?;
+    let header = decode_header_rlp_strict(raw_header)?;
+    let hash_prefix = header.hash_slow()[0];
+    if *record_prefix != hash_prefix {
+        return Err(ErigonSnapshotError::HashPrefixMismatch {
+            record_prefix: *record_prefix,
+            hash_prefix,
+        });
+    }
+    Ok(header)
+}
+
+/// Marker of the chain id stamp at the end of the `extra_data` of private chains' headers.
+#[cfg(feature = "private-chains")]
+const CHAIN_ID_STAMP_MARKER: &[u8; 4] = b"cid:";
+
+/// Returns the chain id stamped at the end of the header's `extra_data`, as done by some private
+/// chains.
+///
+/// The stamp is the `cid:` marker followed by the chain id as an 8-byte little-endian integer, in
+/// the last 12 bytes of the `extra_data`. Returns `None` if the `extra_data` doesn't end with a
+/// stamp.
+#[cfg(feature = "private-chains")]
+pub fn extra_data_chain_hint(header: &Header) -> Option<u64> {
+    let stamp_start = header
+        .extra_data
+        .len()
+        .checked_sub(CHAIN_ID_STAMP_MARKER.len() + 8)?;
+    let (marker, chain_id) = header.extra_data[stamp_start..].split_at(CHAIN_ID_STAMP_MARKER.len());
+    if marker != CHAIN_ID_STAMP_MARKER {
+        return None;
+    }
+    chain_id.try_into().ok().map(u64::from_le_bytes)
+}
+
+/// Decodes the RLP encoded header, rejecting inputs that don't round-trip.
+///
+/// Besides regular decoding, the header is re-encoded and its length compared to the input, so
+/// that trailing or otherwise non-canonical bytes are rejected.
+pub fn decode_header_rlp_strict(buf: &[u8]) -> alloy::rlp::Result<Header> {
+    let header = Header::decode(&mut &buf[..])?;
+    let encoded_length = header.length();
+    if encoded_length != buf.len() {
+        return Err(alloy::rlp::Error::ListLengthMismatch {
+            expected: encoded_length,
+            got: buf.len(),
+        });
+    }
+    Ok(header)
+}
+
+/// An error decoding a header that must be canonically encoded.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum HeaderError {
+    /// The input isn't a valid RLP encoded header.
+    #[error("invalid RLP encoded header: {0}")]
+    InvalidRlp(alloy::rlp::Error),
+    /// The input decodes to a header, but isn't its canonical encoding, so the hash of the input
+    /// isn't the hash of the header.
+    #[error("header isn't canonically encoded")]
+    NonCanonicalEncoding,
+}
+
+/// Decodes the RLP encoded header, requiring the input to be its canonical encoding, i.e. that
+/// re-encoding the header reproduces the exact input bytes.
+///
+/// Content keyed by the block hash must use this, as a non-canonical encoding (e.g. integers with
+/// leading zeros or non-minimal length prefixes) would have a different hash than the header.
+pub fn decode_header_rlp_canonical(buf: &[u8]) -> Result<Header, HeaderError> {
+    let header = Header::decode(&mut &buf[..]).map_err(|err| match err {
+        alloy::rlp::Error::LeadingZero
+        | alloy::rlp::Error::NonCanonicalSingleByte
+        | alloy::rlp::Error::NonCanonicalSize => HeaderError::NonCanonicalEncoding,
+        err => HeaderError::InvalidRlp(err),
+    })?;
+    if alloy::rlp::encode(&header) != buf {
+        return Err(HeaderError::NonCanonicalEncoding);
+    }
+    Ok(header)
+}
+
+/// Decodes the RLP encoded header, rejecting it if its `extra_data` is longer than
+/// `max_extra_data_len`.
+///
+/// The length of the `extra_data` is read from its RLP prefix before any field is decoded, so that
+/// untrusted inputs can't force a large allocation for it.
+pub fn decode_header_rlp_bounded(
+    buf: &[u8],
+    max_extra_data_len: usize,
+) -> alloy::rlp::Result<Header> {
+    let mut payload = buf;
+    if !alloy::rlp::Header::decode(&mut payload)?.list {
+        return Err(alloy::rlp::Error::UnexpectedString);
+    }
+    for _ in 0..EXTRA_DATA_FIELD_INDEX {
+        let field = alloy::rlp::Header::decode(&mut payload)?;
+        payload = payload
+            .get(field.payload_length..)
+            .ok_or(alloy::rlp::Error::InputTooShort)?;
+    }
+    let extra_data = alloy::rlp::Header::decode(&mut payload)?;
+    if extra_data.payload_length > max_extra_data_len {
+        return Err(alloy::rlp::Error::Custom(
+            "extra_data is longer than the maximum length",
+        ));
+    }
+    Header::decode(&mut &buf[..])
+}
+
+/// Decodes the RLP encoded header, rejecting it if its `gas_used` exceeds its `gas_limit`.
+///
+/// Regular decoding accepts such headers, which can't be part of a valid chain, so this should be
+/// used when importing headers from untrusted sources.
+pub fn decode_header_rlp_validated(buf: &[u8]) -> alloy::rlp::Result<Header> {
+    let header = Header::decode(&mut &buf[..])?;
+    if header.gas_used > header.gas_limit {
+        return Err(alloy::rlp::Error::Custom("gas_used exceeds gas_limit"));
+    }
+    Ok(header)
+}
+
+/// Generates an arbitrary header, whose optional fields match the layout of a random fork.
+///
+/// Unlike alloy's `Arbitrary` implementation of the header, an optional field is only present if
+/// all the fields introduced by earlier forks are present too, so the header round-trips through
+/// RLP encoding.
+#[cfg(feature = "arbitrary")]
+pub fn arbitrary_header(u: &mut arbitrary::Unstructured<'_>) -> arbitrary::Result<Header> {
+    let header: Header = u.arbitrary()?;
+    let fork = *u.choose(&[
+        HeaderFork::Frontier,
+        HeaderFork::London,
+        HeaderFork::Shanghai,
+        HeaderFork::Cancun,
+        HeaderFork::Prague,
+    ])?;
+    fn field<'a, T: arbitrary::Arbitrary<'a>>(
+        u: &mut arbitrary::Unstructured<'a>,
+        present: bool,
+    ) -> arbitrary::Result<Option<T>> {
+        Ok(match present {
+            true => Some(u.arbitrary()?),
+            false => None,
+        })
+    }
+    Ok(Header {
+        base_fee_per_gas: field(u, fork >= HeaderFork::London)?,
+        withdrawals_root: field(u, fork >= HeaderFork::Shanghai)?,
+        blob_gas_used: field(u, fork >= HeaderFork::Cancun)?,
+        excess_blob_gas: field(u, fork >= HeaderFork::Cancun)?,
+        parent_beacon_block_root: field(u, fork >= HeaderFork::Cancun)?,
+        requests_hash: field(u, fork >= HeaderFork::Prague)?,
+        ..header
+    })
+}
+
+/// Decodes the RLP encoded header, together with the fork implied by its fields and timestamp.
+///
+/// Headers whose fields don't match the fork at their timestamp are still returned, see
+/// [DetectedFork::is_consistent].
+pub fn decode_header_rlp_detect(buf: &[u8]) -> alloy::rlp::Result<(Header, DetectedFork)> {
+    let header = Header::decode(&mut &buf[..])?;
+    let detected_fork = DetectedFork {
+        fields_fork: HeaderFork::from_fields(&header),
+        timestamp_fork: HeaderFork::from_timestamp(header.timestamp),
+    };
+    Ok((header, detected_fork))
+}
+
+/// An error decoding the raw header returned by geth's `debug_getRawHeader`.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum RawHeaderError {
+    /// The response isn't valid hex.
+    #[error("invalid hex: {0}")]
+    InvalidHex(#[from] alloy::hex::FromHexError),
+    /// The response isn't a valid RLP encoded header.
+    #[error("invalid RLP encoded header: {0}")]
+    InvalidRlp(#[from] alloy::rlp::Error),
+    /// The fields of the header don't match the fork active at its timestamp on the network.
+    #[error("header has the fields of {fields_fork:?}, but {timestamp_fork:?} is active at its timestamp")]
+    UnexpectedFork {
+        fields_fork: HeaderFork,
+        timestamp_fork: HeaderFork,
+    },
+}
+
+/// Decodes the hex encoded RLP header returned by geth's `debug_getRawHeader`, checking that its
+/// fields match the fork active at its timestamp on the network.
+pub fn header_from_debug_raw_header(
+    raw_header: &str,
+    network: KnownNetwork,
+) -> Result<Header, RawHeaderError> {
+    let raw_header = alloy::hex::decode(raw_header.trim())?;
+    let header = Header::decode(&mut raw_header.as_slice())?;
+    let fields_fork = HeaderFork::from_fields(&header);
+    let timestamp_fork = fork_at_timestamp(header.timestamp, network);
+    if fields_fork != timestamp_fork {
+        return Err(RawHeaderError::UnexpectedFork {
+            fields_fork,
+            timestamp_fork,
+        });
+    }
+    Ok(header)
+}
+
+/// Returns the blob base fee of each header, computed from its `excess_blob_gas` (EIP-4844).
+///
+/// The blob base fee update fraction is the one of the fork active at the header's timestamp
+/// (it was changed in Prague by EIP-7691). Pre-Cancun headers don't have a blob base fee, so
+/// their entries are `None`.
+pub fn blob_base_fee_series(headers: &[Header]) -> Vec<Option<U256>> {
+    headers.iter().map(blob_base_fee).collect()
+}
+
+/// Returns the blob fees burnt by the block (EIP-4844), i.e. `blob_gas_used * blob_base_fee`, or
+/// `None` for pre-Cancun headers.
+///
+/// The blob base fee is computed from the header's `excess_blob_gas`, like in
+/// [blob_base_fee_series].
+pub fn blob_fee_burnt(header: &Header) -> Option<U256> {
+    let blob_gas_used = header.blob_gas_used?;
+    blob_base_fee(header).map(|blob_base_fee| blob_base_fee * U256::from(blob_gas_used))
+}
+
+/// Returns the blob base fee of the header, with the blob base fee update fraction of the fork
+/// active at its timestamp.
+fn blob_base_fee(header: &Header) -> Option<U256> {
+    let blob_params = match HeaderFork::from_timestamp(header.timestamp) {
+        HeaderFork::Prague => BlobParams::prague(),
+        _ => BlobParams::cancun(),
+    };
+    header.blob_fee(blob_params).map(U256::from)
+}
+
+/// Returns whether the header is the London activation block of the network.
+///
+/// The base fee of the London activation block is initialized to `INITIAL_BASE_FEE` (EIP-1559),
+/// rather than derived from its parent.
+pub fn is_london_activation(header: &Header, network: KnownNetwork) -> bool {
+    match network {
+        KnownNetwork::Mainnet => header.number == LONDON_BLOCK_NUMBER,
+        // London is active from genesis
+        KnownNetwork::Sepolia | KnownNetwork::Holesky => header.number == 0,
+    }
+}
+
+/// Returns a short identifier of the block for logs and metrics, made of the block number and the
+/// first 4 bytes of the block hash, e.g. `14764013@0x720704f3`.
+pub fn short_id(header: &Header) -> String {
+    format!("{}@{}", header.number, hex_encode(&header.hash_slow()[..4]))
+}
+
+/// Returns the fees burnt by the block (EIP-1559), i.e. `base_fee_per_gas * gas_used`, or `None`
+/// for pre-London headers.
+pub fn burnt_fees(header: &Header) -> Option<U256> {
+    header
+        .base_fee_per_gas
+        .map(|base_fee_per_gas| U256::from(base_fee_per_gas) * U256::from(header.gas_used))
+}
+
+/// Returns the PoW nonce of the header, as its big-endian 8-byte value, or `None` for post-merge
+/// headers, whose nonce is always zero (EIP-3675).
+///
+/// The nonce is RLP encoded with all its 8 bytes, like geth's `BlockNonce`, rather than as a
+/// minimal-length integer, so leading zero bytes are preserved.
+pub fn nonce_bytes(header: &Header) -> Option<[u8; 8]> {
+    match header.number < MERGE_BLOCK_NUMBER {
+        true => Some(header.nonce.0),
+        false => None,
+    }
+}
+
+/// Returns the number of blobs of the block (EIP-4844), i.e. `blob_gas_used / DATA_GAS_PER_BLOB`.
+///
+/// Returns `None` for pre-Cancun headers, and for headers whose `blob_gas_used` isn't a multiple
+/// of the blob gas per blob, which can't be produced by any set of blobs.
+pub fn blob_count(header: &Header) -> Option<u64> {
+    let blob_gas_used = header.blob_gas_used?;
+    match blob_gas_used % DATA_GAS_PER_BLOB {
+        0 => Some(blob_gas_used / DATA_GAS_PER_BLOB),
+        _ => None,
+    }
+}
+
+/// Returns whether the item (e.g. a log address or topic) could be in the logs of the block,
+/// according to the header's `logs_bloom`.
+///
+/// The bloom filter has false positives, so `true` only means a possible membership, while `false`
+/// means the item is definitely absent.
+pub fn bloom_contains(header: &Header, item: &[u8]) -> bool {
+    header.logs_bloom.contains_input(BloomInput::Raw(item))
+}
+
+/// Returns the base reward of the block's miner (excluding transaction fees and uncle
+/// inclusion rewards), on mainnet, or `None` for post-merge headers.
+pub fn block_reward(header: &Header) -> Option<U256> {
+    let block_reward = match header.number {
+        MERGE_BLOCK_NUMBER.. => return None,
+        CONSTANTINOPLE_BLOCK_NUMBER.. => CONSTANTINOPLE_BLOCK_REWARD,
+        BYZANTIUM_BLOCK_NUMBER.. => BYZANTIUM_BLOCK_REWARD,
+        _ => FRONTIER_BLOCK_REWARD,
+    };
+    Some(U256::from(block_reward))
+}
+
+/// Returns the delay (in blocks) of the difficulty bomb that applies to the header, on mainnet.
+///
+/// Headers before Byzantium have no delay, and post-merge headers return `None`, as they have no
+/// difficulty.
+pub fn difficulty_bomb_delay(header: &Header) -> Option<u64> {
+    if header.number >= MERGE_BLOCK_NUMBER {
+        return None;
+    }
+    let delay = DIFFICULTY_BOMB_DELAYS
+        .iter()
+        .rev()
+        .find(|(activation_block, _)| header.number >= *activation_block)
+        .map_or(0, |(_, delay)| *delay);
+    Some(delay)
+}
+
+/// Returns the base fee of the block following the parent header.
+///
+/// Unlike alloy's `Header::next_block_base_fee`, this handles the London activation block, whose
+/// base fee is `INITIAL_BASE_FEE` even though its parent doesn't have a base fee.
+///
+/// Returns `None` if the parent has no base fee, or if it's the last possible block number.
+pub fn next_block_base_fee(
+    parent: &Header,
+    network: KnownNetwork,
+    base_fee_params: BaseFeeParams,
+) -> Option<u64> {
+    let next_header = Header {
+        number: parent.number.checked_add(1)?,
+        ..Default::default()
+    };
+    match is_london_activation(&next_header, network) {
+        true => Some(INITIAL_BASE_FEE),
+        false => parent.next_block_base_fee(base_fee_params),
+    }
+}
+
+/// Maximum length of the `extra_data` in the fixed layout of the header.
+const FIXED_LAYOUT_MAX_EXTRA_DATA_LENGTH: usize = 32;
+
+/// Size of the fixed layout of the header, see [header_to_fixed_layout].
+pub const FIXED_HEADER_LAYOUT_SIZE: usize = 694;
+
+/// Encodes the header into a fixed-size layout, meant for columnar storage with random access by
+/// offset. Unlike RLP, every field is always at the same offset.
+///
+/// The layout is the concatenation of (694 bytes in total):
+/// - a presence bitmap of the optional fields (1 byte), where bits 0 to 5 are set if
+///   `base_fee_per_gas`, `withdrawals_root`, `blob_gas_used`, `excess_blob_gas`,
+///   `parent_beacon_block_root` and `requests_hash` are present, respectively
+/// - `parent_hash` and `ommers_hash` (32 bytes each)
+/// - `beneficiary` (20 bytes)
+/// - `state_root`, `transactions_root` and `receipts_root` (32 bytes each)
+/// - `logs_bloom` (256 bytes)
+/// - `difficulty` (32 bytes, big-endian)
+/// - `number`, `gas_limit`, `gas_used` and `timestamp` (8 bytes each, big-endian)
+/// - the length of `extra_data` (1 byte), followed by `extra_data` padded with zeros to 32 bytes
+/// - `mix_hash` (32 bytes) and `nonce` (8 bytes)
+/// - the optional fields, in the order of the bitmap: `base_fee_per_gas`, `blob_gas_used` and
+///   `excess_blob_gas` (8 bytes each, big-endian), `withdrawals_root`, `parent_beacon_block_root`
+///   and `requests_hash` (32 bytes each). Absent fields are filled with zeros.
+///
+/// Returns `None` if the `extra_data` is longer than 32 bytes, which is not valid on mainnet.
+pub fn header_to_fixed_layout(header: &Header) -> Option<[u8; FIXED_HEADER_LAYOUT_SIZE]> {
+    if header.extra_data.len() > FIXED_LAYOUT_MAX_EXTRA_DATA_LENGTH {
+        return None;
+    }
+
+    let optional_fields = [
+        header.base_fee_per_gas.is_some(),
+        header.withdrawals_root.is_some(),
+        header.blob_gas_used.is_some(),
+        header.excess_blob_gas.is_some(),
+        header.parent_beacon_block_root.is_some(),
+        header.requests_hash.is_some(),
+    ];
+    let bitmap = optional_fields
+        .iter()
+        .enumerate()
+        .fold(0u8, |bitmap, (bit, present)| {
+            bitmap | ((*present as u8) << bit)
+        });
+
+    let mut buf = Vec::with_capacity(FIXED_HEADER_LAYOUT_SIZE);
+    buf.push(bitmap);
+    buf.extend_from_slice(header.parent_hash.as_slice());
+    buf.extend_from_slice(header.ommers_hash.as_slice());
+    buf.extend_from_slice(header.beneficiary.as_slice());
+    buf.extend_from_slice(header.state_root.as_slice());
+    buf.extend_from_slice(header.transactions_root.as_slice());
+    buf.extend_from_slice(header.receipts_root.as_slice());
+    buf.extend_from_slice(header.logs_bloom.as_slice());
+    buf.extend_from_slice(&header.difficulty.to_be_bytes::<32>());
+    buf.extend_from_slice(&header.number.to_be_bytes());
+    buf.extend_from_slice(&header.gas_limit.to_be_bytes());
+    buf.extend_from_slice(&header.gas_used.to_be_bytes());
+    buf.extend_from_slice(&header.timestamp.to_be_bytes());
+    buf.push(header.extra_data.len() as u8);
+    buf.extend_from_slice(&header.extra_data);
+    buf.resize(
+        buf.len() + FIXED_LAYOUT_MAX_EXTRA_DATA_LENGTH - header.extra_data.len(),
+        0,
+    );
+    buf.extend_from_slice(header.mix_hash.as_slice());
+    buf.extend_from_slice(header.nonce.as_slice());
+    buf.extend_from_slice(&header.base_fee_per_gas.unwrap_or_default().to_be_bytes());
+    buf.extend_from_slice(header.withdrawals_root.unwrap_or_default().as_slice());
+    buf.extend_from_slice(&header.blob_gas_used.unwrap_or_default().to_be_bytes());
+    buf.extend_from_slice(&header.excess_blob_gas.unwrap_or_default().to_be_bytes());
+    buf.extend_from_slice(
+        header
+            .parent_beacon_block_root
+            .unwrap_or_default()
+            .as_slice(),
+    );
+    buf.extend_from_slice(header.requests_hash.unwrap_or_default().as_slice());
+
+    buf.try_into().ok()
+}
+
+/// Decodes the header from the fixed-size layout, see [header_to_fixed_layout].
+///
+/// Returns `None` if the length of the `extra_data` is longer than 32 bytes.
+pub fn header_from_fixed_layout(buf: &[u8; FIXED_HEADER_LAYOUT_SIZE]) -> Option<Header> {
+    let mut rest = &buf[..];
+    let mut take = |length: usize| {
+        let (field, remaining) = rest.split_at(length);
+        rest = remaining;
+        field
+    };
+    let take_u64 = |field: &[u8]| u64::from_be_bytes(field.try_into().unwrap_or_default());
+
+    let bitmap = take(1)[0];
+    let present = |bit: u8| bitmap & (1 << bit) != 0;
+    let parent_hash = B256::from_slice(take(32));
+    let ommers_hash = B256::from_slice(take(32));
+    let beneficiary = Address::from_slice(take(20));
+    let state_root = B256::from_slice(take(32));
+    let transactions_root = B256::from_slice(take(32));
+    let receipts_root = B256::from_slice(take(32));
+    let logs_bloom = Bloom::from_slice(take(256));
+    let difficulty = U256::from_be_slice(take(32));
+    let number = take_u64(take(8));
+    let gas_limit = take_u64(take(8));
+    let gas_used = take_u64(take(8));
+    let timestamp = take_u64(take(8));
+    let extra_data_length = take(1)[0] as usize;
+    if extra_data_length > FIXED_LAYOUT_MAX_EXTRA_DATA_LENGTH {
+        return None;
+    }
+    let extra_data =
+        Bytes::copy_from_slice(&take(FIXED_LAYOUT_MAX_EXTRA_DATA_LENGTH)[..extra_data_length]);
+    let mix_hash = B256::from_slice(take(32));
+    let nonce = B64::from_slice(take(8));
+    let base_fee_per_gas = take_u64(take(8));
+    let withdrawals_root = B256::from_slice(take(32));
+    let blob_gas_used = take_u64(take(8));
+    let excess_blob_gas = take_u64(take(8));
+    let parent_beacon_block_root = B256::from_slice(take(32));
+    let requests_hash = B256::from_slice(take(32));
+
+    Some(Header {
+        parent_hash,
+        ommers_hash,
+        beneficiary,
+        state_root,
+        transactions_root,
+        receipts_root,
+        logs_bloom,
+        difficulty,
+        number,
+        gas_limit,
+        gas_used,
+        timestamp,
+        extra_data,
+        mix_hash,
+        nonce,
+        base_fee_per_gas: present(0).then_some(base_fee_per_gas),
+        withdrawals_root: present(1).then_some(withdrawals_root),
+        blob_gas_used: present(2).then_some(blob_gas_used),
+        excess_blob_gas: present(3).then_some(excess_blob_gas),
+        parent_beacon_block_root: present(4).then_some(parent_beacon_block_root),
+        requests_hash: present(5).then_some(requests_hash),
+    })
+}
+
+/// An error converting a header to the consensus layer's `ExecutionPayloadHeader`.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ConversionError {
+    /// Pre-merge headers aren't part of a beacon block.
+    #[error("header {block_number} is a pre-merge header")]
+    PreMergeHeader { block_number: u64 },
+    /// The optional fields of the header don't match the fields of the fork.
+    #[error("header {block_number} doesn't have the fields of {fork}")]
+    InvalidFieldsForFork { block_number: u64, fork: ForkName },
+    #[error("extra data of length {length} is longer than 32 bytes")]
+    ExtraDataTooLong { length: usize },
+}
+
+/// Converts the header to the consensus layer's `ExecutionPayloadHeader` of the fork.
+///
+/// The header must have exactly the fields of the fork: `base_fee_per_gas` from Bellatrix,
+/// `withdrawals_root` from Capella, and `blob_gas_used` and `excess_blob_gas` from Deneb.
+pub fn header_to_execution_payload_header(
+    header: &Header,
+    fork: ForkName,
+) -> Result<ExecutionPayloadHeader, ConversionError> {
+    if !header.difficulty.is_zero() {
+        return Err(ConversionError::PreMergeHeader {
+            block_number: header.number,
+        });
+    }
+    let invalid_fields = || ConversionError::InvalidFieldsForFork {
+        block_number: header.number,
+        fork,
+    };
+    let expected_fork = match fork {
+        ForkName::Bellatrix => HeaderFork::London,
+        ForkName::Capella => HeaderFork::Shanghai,
+        // The `parent_beacon_block_root` isn't part of the `ExecutionPayloadHeader`
+        ForkName::Deneb => HeaderFork::Cancun,
+    };
+    if HeaderFork::from_fields(header) != expected_fork {
+        return Err(invalid_fields());
+    }
+    let extra_data = ExtraData::new(header.extra_data.to_vec()).map_err(|_| {
+        ConversionError::ExtraDataTooLong {
+            length: header.extra_data.len(),
+        }
+    })?;
+    let base_fee_per_gas = header
+        .base_fee_per_gas
+        .map(U256::from)
+        .ok_or_else(invalid_fields)?;
+
+    let bellatrix = ExecutionPayloadHeaderBellatrix {
+        parent_hash: header.parent_hash,
+        fee_recipient: header.beneficiary,
+        state_root: header.state_root,
+        receipts_root: header.receipts_root,
+        logs_bloom: header.logs_bloom.to_vec().into(),
+        prev_randao: header.mix_hash,
+        block_number: header.number,
+        gas_limit: header.gas_limit,
+        gas_used: header.gas_used,
+        timestamp: header.timestamp,
+        extra_data,
+        base_fee_per_gas,
+        block_hash: header.hash_slow(),
+        transactions_root: header.transactions_root,
+    };
+    if fork == ForkName::Bellatrix {
+        return Ok(ExecutionPayloadHeader::Bellatrix(bellatrix));
+    }
+
+    let capella = ExecutionPayloadHeaderCapella {
+        parent_hash: bellatrix.parent_hash,
+        fee_recipient: bellatrix.fee_recipient,
+        state_root: bellatrix.state_root,
+        receipts_root: bellatrix.receipts_root,
+        logs_bloom: bellatrix.logs_bloom,
+        prev_randao: bellatrix.prev_randao,
+        block_number: bellatrix.block_number,
+        gas_limit: bellatrix.gas_limit,
+        gas_used: bellatrix.gas_used,
+        timestamp: bellatrix.timestamp,
+        extra_data: bellatrix.extra_data,
+        base_fee_per_gas: bellatrix.base_fee_per_gas,
+        block_hash: bellatrix.block_hash,
+        transactions_root: bellatrix.transactions_root,
+        withdrawals_root: header.withdrawals_root.ok_or_else(invalid_fields)?,
+    };
+    if fork == ForkName::Capella {
+        return Ok(ExecutionPayloadHeader::Capella(capella));
+    }
+
+    Ok(ExecutionPayloadHeader::Deneb(ExecutionPayloadHeaderDeneb {
+        parent_hash: capella.parent_hash,
+        fee_recipient: capella.fee_recipient,
+        state_root: capella.state_root,
+        receipts_root: capella.receipts_root,
+        logs_bloom: capella.logs_bloom,
+        prev_randao: capella.prev_randao,
+        block_number: capella.block_number,
+        gas_limit: capella.gas_limit,
+        gas_used: capella.gas_used,
+        timestamp: capella.timestamp,
+        extra_data: capella.extra_data,
+        base_fee_per_gas: capella.base_fee_per_gas,
+        block_hash: capella.block_hash,
+        transactions_root: capella.transactions_root,
+        withdrawals_root: capella.withdrawals_root,
+        blob_gas_used: header.blob_gas_used.ok_or_else(invalid_fields)?,
+        excess_blob_gas: header.excess_blob_gas.ok_or_else(invalid_fields)?,
+    }))
+}
+
+/// Checks whether the header matches the consensus layer's `ExecutionPayloadHeader`, e.g. one
+/// received through beacon gossip.
+pub fn header_matches_execution_payload_header(
+    header: &Header,
+    execution_payload_header: &ExecutionPayloadHeader,
+) -> bool {
+    let fork = match execution_payload_header {
+        ExecutionPayloadHeader::Bellatrix(_) => ForkName::Bellatrix,
+        ExecutionPayloadHeader::Capella(_) => ForkName::Capella,
+        ExecutionPayloadHeader::Deneb(_) => ForkName::Deneb,
+    };
+    match (
+        header_to_execution_payload_header(header, fork),
+        execution_payload_header,
+    ) {
+        (
+            Ok(ExecutionPayloadHeader::Bellatrix(converted)),
+            ExecutionPayloadHeader::Bellatrix(expected),
+        ) => converted == *expected,
+        (
+            Ok(ExecutionPayloadHeader::Capella(converted)),
+            ExecutionPayloadHeader::Capella(expected),
+        ) => converted == *expected,
+        (Ok(ExecutionPayloadHeader::Deneb(converted)), ExecutionPayloadHeader::Deneb(expected)) => {
+            converted == *expected
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::str::FromStr;
+
+    use alloy::consensus::{BlockHeader, EMPTY_OMMER_ROOT_HASH};
+    use rstest::rstest;
+    use serde_json::Value;
+
+    use super::*;
+
+    fn get_header(block_number: u64) -> Header {
+        let file = std::fs::read_to_string(format!(
+            "../../test_assets/mainnet/block_{block_number}_value.json"
+        ))
+        .unwrap();
+        let json: Value = serde_json::from_str(&file).unwrap();
+        serde_json::from_value(json["result"].clone()).unwrap()
+    }
+
+    #[test]
+    fn parent_beacon_root_matches() {
+        // Cancun block
+        let header = get_header(19_433_902);
+        let parent_beacon_block_root =
+            B256::from_str("0xdc172956fa107b283fd107798ebf7a5e329bcdaaf9f33c061331d3bbe40b18f3")
+                .unwrap();
+
+        assert_eq!(
+            header_parent_beacon_root_matches(&header, parent_beacon_block_root),
+            Some(true)
+        );
+        assert_eq!(
+            header_parent_beacon_root_matches(&header, B256::random()),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn parent_beacon_root_pre_cancun() {
+        // Shanghai block
+        let header = get_header(17_034_871);
+
+        assert_eq!(
+            header_parent_beacon_root_matches(&header, B256::random()),
+            None
+        );
+    }
+
+    #[rstest]
+    #[case::london(14_764_013)]
+    #[case::shanghai(17_034_871)]
+    #[case::cancun(19_433_902)]
+    fn decode_strict_canonical(#[case] block_number: u64) {
+        let header = get_header(block_number);
+        let rlp = alloy::rlp::encode(&header);
+
+        assert_eq!(decode_header_rlp_strict(&rlp).unwrap(), header);
+    }
+
+    #[test]
+    fn decode_strict_rejects_trailing_bytes() {
+        let mut rlp = alloy::rlp::encode(get_header(14_764_013));
+        rlp.push(0x80);
+
+        // Regular decoding ignores the trailing bytes
+        Header::decode(&mut rlp.as_slice()).unwrap();
+        assert!(matches!(
+            decode_header_rlp_strict(&rlp),
+            Err(alloy::rlp::Error::ListLengthMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn decode_strict_rejects_non_canonical_length_prefix() {
+        // Encode the list length with 3 bytes instead of 2
+        let rlp = alloy::rlp::encode(get_header(14_764_013));
+        assert_eq!(rlp[0], 0xf9);
+        let mut non_canonical = vec![0xfa, 0x00];
+        non_canonical.extend_from_slice(&rlp[1..]);
+
+        assert!(decode_header_rlp_strict(&non_canonical).is_err());
+    }
+
+    #[test]
+    fn decode_canonical() {
+        let header = get_header(14_764_013);
+        let rlp = alloy::rlp::encode(&header);
+        assert_eq!(decode_header_rlp_canonical(&rlp), Ok(header.clone()));
+
+        // Encode the gas limit with a leading zero byte, and fix the list length prefix
+        let gas_limit = alloy::rlp::encode(header.gas_limit);
+        let position = rlp
+            .windows(gas_limit.len())
+            .position(|window| window == gas_limit)
+            .unwrap();
+        let mut non_canonical = rlp[..position].to_vec();
+        non_canonical.push(gas_limit[0] + 1);
+        non_canonical.push(0);
+        non_canonical.extend_from_slice(&rlp[position + 1..]);
+        let payload_length = u16::from_be_bytes([rlp[1], rlp[2]]) + 1;
+        non_canonical[1..3].copy_from_slice(&payload_length.to_be_bytes());
+        assert_eq!(
+            decode_header_rlp_canonical(&non_canonical),
+            Err(HeaderError::NonCanonicalEncoding)
+        );
+
+        let mut trailing_bytes = rlp.clone();
+        trailing_bytes.push(0x80);
+        assert_eq!(
+            decode_header_rlp_canonical(&trailing_bytes),
+            Err(HeaderError::NonCanonicalEncoding)
+        );
+        assert!(matches!(
+            decode_header_rlp_canonical(&rlp[..100]),
+            Err(HeaderError::InvalidRlp(_))
+        ));
+    }
+
+    #[test]
+    fn decode_bounded() {
+        let header = get_header(14_764_013);
+        let rlp = alloy::rlp::encode(&header);
+        let extra_data_len = header.extra_data.len();
+
+        assert_eq!(
+            decode_header_rlp_bounded(&rlp, extra_data_len).unwrap(),
+            header
+        );
+        assert_eq!(
+            decode_header_rlp_bounded(&rlp, extra_data_len - 1),
+            Err(alloy::rlp::Error::Custom(
+                "extra_data is longer than the maximum length"
+            ))
+        );
+    }
+
+    #[test]
+    fn decode_bounded_rejects_huge_extra_data_early() {
+        let header = Header {
+            extra_data: vec![0; 8 * 1024 * 1024].into(),
+            ..get_header(14_764_013)
+        };
+        let rlp = alloy::rlp::encode(&header);
+
+        // Regular decoding copies the whole extra_data
+        assert_eq!(Header::decode(&mut rlp.as_slice()).unwrap(), header);
+        assert_eq!(
+            decode_header_rlp_bounded(&rlp, 32),
+            Err(alloy::rlp::Error::Custom(
+                "extra_data is longer than the maximum length"
+            ))
+        );
+    }
+
+    #[test]
+    fn decode_validated() {
+        let header = get_header(14_764_013);
+        let rlp = alloy::rlp::encode(&header);
+        assert_eq!(decode_header_rlp_validated(&rlp).unwrap(), header);
+
+        let header = Header {
+            gas_used: header.gas_limit,
+            ..header
+        };
+        let rlp = alloy::rlp::encode(&header);
+        assert_eq!(decode_header_rlp_validated(&rlp).unwrap(), header);
+
+        let header = Header {
+            gas_used: header.gas_limit + 1,
+            ..header
+        };
+        let rlp = alloy::rlp::encode(&header);
+        // Regular decoding accepts the header
+        Header::decode(&mut rlp.as_slice()).unwrap();
+        assert_eq!(
+            decode_header_rlp_validated(&rlp),
+            Err(alloy::rlp::Error::Custom("gas_used exceeds gas_limit"))
+        );
+    }
+
+    #[rstest]
+    #[case::london(14_764_013, HeaderFork::London)]
+    #[case::shanghai(17_034_871, HeaderFork::Shanghai)]
+    #[case::cancun(19_433_902, HeaderFork::Cancun)]
+    fn decode_detect(#[case] block_number: u64, #[case] fork: HeaderFork) {
+        let header = get_header(block_number);
+        let rlp = alloy::rlp::encode(&header);
+
+        let (decoded, detected_fork) = decode_header_rlp_detect(&rlp).unwrap();
+        assert_eq!(decoded, header);
+        assert_eq!(
+            detected_fork,
+            DetectedFork {
+                fields_fork: fork,
+                timestamp_fork: fork,
+            }
+        );
+        assert!(detected_fork.is_consistent());
+    }
+
+    #[test]
+    fn decode_detect_frontier() {
+        let header = Header {
+            timestamp: 1438269988,
+            ..Default::default()
+        };
+        let rlp = alloy::rlp::encode(&header);
+
+        let (_, detected_fork) = decode_header_rlp_detect(&rlp).unwrap();
+        assert_eq!(detected_fork.fields_fork, HeaderFork::Frontier);
+        assert!(detected_fork.is_consistent());
+    }
+
+    #[test]
+    fn decode_detect_prague() {
+        let header = Header {
+            timestamp: PRAGUE_TIMESTAMP,
+            requests_hash: Some(B256::random()),
+            ..get_header(19_433_902)
+        };
+        let rlp = alloy::rlp::encode(&header);
+
+        let (_, detected_fork) = decode_header_rlp_detect(&rlp).unwrap();
+        assert_eq!(detected_fork.fields_fork, HeaderFork::Prague);
+        assert!(detected_fork.is_consistent());
+    }
+
+    #[test]
+    fn decode_detect_inconsistent() {
+        // London header with a timestamp after Shanghai
+        let header = Header {
+            timestamp: SHANGHAI_TIMESTAMP + 12,
+            ..get_header(14_764_013)
+        };
+        let rlp = alloy::rlp::encode(&header);
+
+        let (_, detected_fork) = decode_header_rlp_detect(&rlp).unwrap();
+        assert_eq!(
+            detected_fork,
+            DetectedFork {
+                fields_fork: HeaderFork::London,
+                timestamp_fork: HeaderFork::Shanghai,
+            }
+        );
+        assert!(!detected_fork.is_consistent());
+    }
+
+    #[rstest]
+    #[case::mainnet_frontier(KnownNetwork::Mainnet, LONDON_TIMESTAMP - 1, HeaderFork::Frontier)]
+    #[case::mainnet_london(KnownNetwork::Mainnet, LONDON_TIMESTAMP, HeaderFork::London)]
+    #[case::mainnet_shanghai(KnownNetwork::Mainnet, SHANGHAI_TIMESTAMP, HeaderFork::Shanghai)]
+    #[case::mainnet_cancun(KnownNetwork::Mainnet, CANCUN_TIMESTAMP, HeaderFork::Cancun)]
+    #[case::mainnet_prague(KnownNetwork::Mainnet, PRAGUE_TIMESTAMP, HeaderFork::Prague)]
+    #[case::sepolia_genesis(KnownNetwork::Sepolia, 0, HeaderFork::London)]
+    #[case::sepolia_pre_shanghai(KnownNetwork::Sepolia, 1_677_557_087, HeaderFork::London)]
+    #[case::sepolia_shanghai(KnownNetwork::Sepolia, 1_677_557_088, HeaderFork::Shanghai)]
+    #[case::sepolia_cancun(KnownNetwork::Sepolia, 1_706_655_072, HeaderFork::Cancun)]
+    #[case::sepolia_prague(KnownNetwork::Sepolia, 1_741_159_776, HeaderFork::Prague)]
+    #[case::holesky_genesis(KnownNetwork::Holesky, 1_695_902_400, HeaderFork::London)]
+    #[case::holesky_shanghai(KnownNetwork::Holesky, 1_696_000_704, HeaderFork::Shanghai)]
+    #[case::holesky_cancun(KnownNetwork::Holesky, 1_707_305_664, HeaderFork::Cancun)]
+    #[case::holesky_pre_prague(KnownNetwork::Holesky, 1_740_434_111, HeaderFork::Cancun)]
+    #[case::holesky_prague(KnownNetwork::Holesky, 1_740_434_112, HeaderFork::Prague)]
+    fn fork_at_network_timestamp(
+        #[case] network: KnownNetwork,
+        #[case] timestamp: u64,
+        #[case] expected: HeaderFork,
+    ) {
+        assert_eq!(fork_at_timestamp(timestamp, network), expected);
+        if network == KnownNetwork::Mainnet {
+            assert_eq!(HeaderFork::from_timestamp(timestamp), expected);
+        }
+    }
+
+    #[test]
+    fn decode_debug_raw_header() {
+        // `debug_getRawHeader` response of the block 15537393
+        let raw_header = "0xf9021ba02b3ea3cd4befcab070812443affb08bf17a91ce382c714a536ca3cacab82278ba01dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d4934794829bd824b016326a401d083b33d092293333a830a04919dafa6ac8becfbbd0c2808f6c9511a057c21e42839caff5dfb6d3ef514951a0dd5eec02b019ff76e359b09bfa19395a2a0e97bc01e70d8d5491e640167c96a8a0baa842cfd552321a9c2450576126311e071680a1258032219c6490b663c1dab8b90100000004000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000800000000000000000000000080000000000000000000000000000000000000000000000000200000000000000000008000000000040000000000000100000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000084000000000010020000000000000000000000000000000000020000000200000000200000000000000000000000000000000000000000400000000000000000000000008727472e1db3626a83ed14f18401c9c3808401c9a205846322c96292e4b883e5bda9e7a59ee4bb99e9b1bc460021a04cbec03dddd4b939730a7fe6048729604d4266e82426d472a2b2024f3cc4043f8862a3ee77461d4fc9850a1a4e5f06";
+        let header = header_from_debug_raw_header(raw_header, KnownNetwork::Mainnet).unwrap();
+        assert_eq!(header.number, 15_537_393);
+        assert_eq!(
+            header_from_debug_raw_header(&raw_header[2..], KnownNetwork::Mainnet).unwrap(),
+            header
+        );
+
+        assert!(matches!(
+            header_from_debug_raw_header("0xzz", KnownNetwork::Mainnet),
+            Err(RawHeaderError::InvalidHex(_))
+        ));
+        assert!(matches!(
+            header_from_debug_raw_header(&raw_header[..100], KnownNetwork::Mainnet),
+            Err(RawHeaderError::InvalidRlp(_))
+        ));
+
+        // Pre-London header with a base fee, which is only valid on networks with London at
+        // genesis
+        let raw_header = alloy::hex::encode(alloy::rlp::encode(Header {
+            number: 1_000_000,
+            timestamp: 1,
+            base_fee_per_gas: Some(7),
+            ..Default::default()
+        }));
+        assert_eq!(
+            header_from_debug_raw_header(&raw_header, KnownNetwork::Mainnet),
+            Err(RawHeaderError::UnexpectedFork {
+                fields_fork: HeaderFork::London,
+                timestamp_fork: HeaderFork::Frontier,
+            })
+        );
+        assert!(header_from_debug_raw_header(&raw_header, KnownNetwork::Sepolia).is_ok());
+
+        // Shanghai header after the Cancun activation on Sepolia, but before it on mainnet
+        let raw_header = alloy::hex::encode(alloy::rlp::encode(Header {
+            timestamp: 1_706_655_072,
+            base_fee_per_gas: Some(7),
+            withdrawals_root: Some(EMPTY_ROOT_HASH),
+            ..Default::default()
+        }));
+        assert!(header_from_debug_raw_header(&raw_header, KnownNetwork::Mainnet).is_ok());
+        assert_eq!(
+            header_from_debug_raw_header(&raw_header, KnownNetwork::Sepolia),
+            Err(RawHeaderError::UnexpectedFork {
+                fields_fork: HeaderFork::Shanghai,
+                timestamp_fork: HeaderFork::Cancun,
+            })
+        );
+    }
+
+    // Empty blocks are detected by alloy's `BlockHeader::is_empty`, which checks the
+    // transactions, uncles and (when present) withdrawals roots.
+    #[rstest]
+    #[case::pre_shanghai(14_764_013)]
+    #[case::post_shanghai(19_433_902)]
+    fn is_empty_block(#[case] block_number: u64) {
+        let header = get_header(block_number);
+        assert!(!header.is_empty());
+
+        let empty_header = Header {
+            transactions_root: EMPTY_ROOT_HASH,
+            ommers_hash: EMPTY_OMMER_ROOT_HASH,
+            withdrawals_root: header.withdrawals_root.map(|_| EMPTY_ROOT_HASH),
+            ..header.clone()
+        };
+        assert!(empty_header.is_empty());
+
+        // Block with withdrawals only
+        if header.withdrawals_root.is_some() {
+            let header = Header {
+                withdrawals_root: header.withdrawals_root,
+                ..empty_header
+            };
+            assert!(!header.is_empty());
+        }
+    }
+
+    /// Splits the RLP encoded header into the RLP encoding of each of its fields.
+    fn rlp_fields(encoded_header: &[u8]) -> Vec<Vec<u8>> {
+        let mut buf = encoded_header;
+        let list_header = alloy::rlp::Header::decode(&mut buf).unwrap();
+        assert!(list_header.list);
+        let mut fields = vec![];
+        while !buf.is_empty() {
+            let mut field = buf;
+            let field_header = alloy::rlp::Header::decode(&mut field).unwrap();
+            let field_length = field_header.length() + field_header.payload_length;
+            fields.push(buf[..field_length].to_vec());
+            buf = &buf[field_length..];
+        }
+        fields
+    }
+
+    // Like geth, zero integers are encoded as an empty byte string (0x80), which affects the hash
+    #[test]
+    fn rlp_encode_zero_integer_fields() {
+        let header = Header {
+            difficulty: U256::ZERO,
+            base_fee_per_gas: Some(0),
+            ..get_header(19_433_902)
+        };
+        let encoded_header = alloy::rlp::encode(&header);
+
+        let fields = rlp_fields(&encoded_header);
+        // difficulty and base_fee_per_gas are the 8th and 16th fields
+        assert_eq!(fields[7], vec![0x80]);
+        assert_eq!(fields[15], vec![0x80]);
+        assert_eq!(decode_header_rlp_strict(&encoded_header).unwrap(), header);
+
+        // Post-merge header with zero difficulty, which matches its hash
+        let file =
+            std::fs::read_to_string("../../test_assets/mainnet/block_19433902_value.json").unwrap();
+        let json: Value = serde_json::from_str(&file).unwrap();
+        let header = get_header(19_433_902);
+        assert_eq!(header.difficulty, U256::ZERO);
+        assert_eq!(rlp_fields(&alloy::rlp::encode(&header))[7], vec![0x80]);
+        assert_eq!(
+            header.hash_slow(),
+            B256::from_str(json["result"]["hash"].as_str().unwrap()).unwrap()
+        );
+    }
+
+    fn get_block_hash(block_number: u64) -> B256 {
+        let file = std::fs::read_to_string(format!(
+            "../../test_assets/mainnet/block_{block_number}_value.json"
+        ))
+        .unwrap();
+        let json: Value = serde_json::from_str(&file).unwrap();
+        B256::from_str(json["result"]["hash"].as_str().unwrap()).unwrap()
+    }
+
+    // The optional fields of alloy's header are decoded by the number of RLP items, so the
+    // `withdrawals_root` is only present if the header has the 17th field, regardless of its block
+    // number.
+    #[rstest]
+    #[case::london(14_764_013, 16)]
+    #[case::shanghai(17_034_871, 17)]
+    fn withdrawals_root_by_field_count(#[case] block_number: u64, #[case] field_count: usize) {
+        let header = get_header(block_number);
+        let encoded_header = alloy::rlp::encode(&header);
+        assert_eq!(
+            alloy::primitives::keccak256(&encoded_header),
+            get_block_hash(block_number)
+        );
+
+        let fields = rlp_fields(&encoded_header);
+        assert_eq!(fields.len(), field_count);
+        if let Some(withdrawals_root) = header.withdrawals_root {
+            assert_eq!(fields[16], alloy::rlp::encode(withdrawals_root));
+        }
+        assert_eq!(
+            decode_header_rlp_canonical(&encoded_header).unwrap(),
+            header
+        );
+
+        // London header with the block number of a Shanghai block
+        let header = Header {
+            number: 17_034_871,
+            ..get_header(14_764_013)
+        };
+        let decoded = decode_header_rlp_canonical(&alloy::rlp::encode(&header)).unwrap();
+        assert_eq!(decoded.withdrawals_root, None);
+    }
+
+    // The blob gas fields of Cancun headers follow the `withdrawals_root`, and are part of the
+    // block hash.
+    #[rstest]
+    #[case::cancun(19_433_902)]
+    #[case::cancun_with_blobs(19_433_903)]
+    fn cancun_block_hash(#[case] block_number: u64) {
+        let header = get_header(block_number);
+        assert_eq!(header.hash_slow(), get_block_hash(block_number));
+
+        let fields = rlp_fields(&alloy::rlp::encode(&header));
+        assert_eq!(
+            fields[17],
+            alloy::rlp::encode(header.blob_gas_used.unwrap())
+        );
+        assert_eq!(
+            fields[18],
+            alloy::rlp::encode(header.excess_blob_gas.unwrap())
+        );
+
+        let header = Header {
+            blob_gas_used: header.blob_gas_used.map(|blob_gas_used| blob_gas_used + 1),
+            ..header
+        };
+        assert_ne!(header.hash_slow(), get_block_hash(block_number));
+    }
+
+    // The `parent_beacon_block_root` is the last field of Cancun headers. alloy still decodes a
+    // Cancun header without it, which is rejected once checked against the fork at its timestamp.
+    #[test]
+    fn parent_beacon_block_root_round_trip() {
+        let header = get_header(19_433_902);
+        let encoded_header = alloy::rlp::encode(&header);
+        let fields = rlp_fields(&encoded_header);
+        assert_eq!(fields.len(), 20);
+        assert_eq!(
+            fields[19],
+            alloy::rlp::encode(header.parent_beacon_block_root.unwrap())
+        );
+        assert_eq!(
+            alloy::rlp::encode(decode_header_rlp_canonical(&encoded_header).unwrap()),
+            encoded_header
+        );
+
+        // Cancun header with the blob gas fields, but without the parent beacon block root
+        let incomplete_header = Header {
+            parent_beacon_block_root: None,
+            ..header
+        };
+        let encoded_header = alloy::rlp::encode(&incomplete_header);
+        assert_eq!(rlp_fields(&encoded_header).len(), 19);
+        assert_eq!(
+            header_from_debug_raw_header(
+                &alloy::hex::encode(&encoded_header),
+                KnownNetwork::Mainnet
+            ),
+            Err(RawHeaderError::UnexpectedFork {
+                fields_fork: HeaderFork::Shanghai,
+                timestamp_fork: HeaderFork::Cancun,
+            })
+        );
+    }
+
+    // The `requests_hash` of Prague headers is appended after the `parent_beacon_block_root`, and
+    // is only decoded if the header has the 21st field.
+    #[test]
+    fn requests_hash_by_field_count() {
+        let header = get_header(19_433_902);
+        assert_eq!(header.requests_hash, None);
+        let decoded = decode_header_rlp_canonical(&alloy::rlp::encode(&header)).unwrap();
+        assert_eq!(decoded.requests_hash, None);
+
+        let header = Header {
+            timestamp: PRAGUE_TIMESTAMP,
+            requests_hash: Some(B256::random()),
+            ..header
+        };
+        let encoded_header = alloy::rlp::encode(&header);
+        let fields = rlp_fields(&encoded_header);
+        assert_eq!(fields.len(), 21);
+        assert_eq!(
+            fields[20],
+            alloy::rlp::encode(header.requests_hash.unwrap())
+        );
+        assert_eq!(
+            decode_header_rlp_canonical(&encoded_header).unwrap(),
+            header
+        );
+    }
+
+    #[test]
+    fn expects_withdrawals_from_header() {
+        assert!(!expects_withdrawals(&get_header(14_764_013)));
+
+        let header = get_header(17_034_871);
+        assert!(expects_withdrawals(&header));
+
+        // Shanghai block without withdrawals
+        let header = Header {
+            withdrawals_root: Some(EMPTY_ROOT_HASH),
+            ..header
+        };
+        assert!(!expects_withdrawals(&header));
+    }
+
+    // The ommers hash is alloy's `Header::ommers_hash`, which is called `sha3Uncles` in the
+    // JSON-RPC API and `uncles_hash` in some clients.
+    #[test]
+    fn ommers_hash_rpc_name() {
+        let file =
+            std::fs::read_to_string("../../test_assets/mainnet/block_14764013_value.json").unwrap();
+        let json: Value = serde_json::from_str(&file).unwrap();
+        let header = get_header(14_764_013);
+        assert_eq!(
+            header.ommers_hash,
+            B256::from_str(json["result"]["sha3Uncles"].as_str().unwrap()).unwrap()
+        );
+
+        let serialized = serde_json::to_value(&header).unwrap();
+        assert_eq!(serialized["sha3Uncles"], json["result"]["sha3Uncles"]);
+        assert!(serialized.get("ommersHash").is_none());
+        assert!(serialized.get("unclesHash").is_none());
+    }
+
+    // alloy's header serializes with the field names and hex quantities of the JSON-RPC API, and
+    // without the optional fields that aren't present.
+    #[rstest]
+    #[case::london(14_764_013)]
+    #[case::shanghai(17_034_871)]
+    #[case::cancun(19_433_902)]
+    fn serialize_as_rpc_header(#[case] block_number: u64) {
+        let file = std::fs::read_to_string(format!(
+            "../../test_assets/mainnet/block_{block_number}_value.json"
+        ))
+        .unwrap();
+        let json: Value = serde_json::from_str(&file).unwrap();
+        let header = get_header(block_number);
+
+        let serialized = serde_json::to_value(&header).unwrap();
+        for (field, value) in serialized.as_object().unwrap() {
+            assert_eq!(*value, json["result"][field], "field {field} doesn't match");
+        }
+        assert_eq!(serialized["miner"], json["result"]["miner"]);
+        assert_eq!(serialized["baseFeePerGas"], json["result"]["baseFeePerGas"]);
+
+        let header = Header {
+            base_fee_per_gas: None,
+            ..header
+        };
+        let serialized = serde_json::to_value(&header).unwrap();
+        assert!(serialized.get("baseFeePerGas").is_none());
+        assert_eq!(
+            serde_json::from_value::<Header>(serialized).unwrap(),
+            header
+        );
+    }
+
+    #[test]
+    fn has_transactions_from_header() {
+        let header = get_header(14_764_013);
+        assert!(has_transactions(&header));
+
+        let empty_header = Header {
+            transactions_root: EMPTY_ROOT_HASH,
+            ..header
+        };
+        assert!(!has_transactions(&empty_header));
+    }
+
+    // The next block's base fee is computed by alloy's `Header::next_block_base_fee`, which takes
+    // the EIP-1559 parameters of the chain.
+    #[test]
+    fn next_block_base_fee() {
+        let header = get_header(19_433_902);
+        let next_header = get_header(19_433_903);
+
+        let mainnet_base_fee = header.next_block_base_fee(BaseFeeParams::ethereum());
+        assert_eq!(mainnet_base_fee, next_header.base_fee_per_gas);
+
+        let custom_base_fee = header.next_block_base_fee(BaseFeeParams::new(50, 6));
+        assert!(custom_base_fee.is_some());
+        assert_ne!(custom_base_fee, mainnet_base_fee);
+    }
+
+    #[rstest]
+    #[case::mainnet_genesis(KnownNetwork::Mainnet, 0, false)]
+    #[case::mainnet_pre_london(KnownNetwork::Mainnet, LONDON_BLOCK_NUMBER - 1, false)]
+    #[case::mainnet_london_activation(KnownNetwork::Mainnet, LONDON_BLOCK_NUMBER, true)]
+    #[case::mainnet_post_london(KnownNetwork::Mainnet, LONDON_BLOCK_NUMBER + 1, false)]
+    #[case::sepolia_genesis(KnownNetwork::Sepolia, 0, true)]
+    #[case::sepolia_post_genesis(KnownNetwork::Sepolia, 1, false)]
+    #[case::sepolia_mainnet_london(KnownNetwork::Sepolia, LONDON_BLOCK_NUMBER, false)]
+    #[case::holesky_genesis(KnownNetwork::Holesky, 0, true)]
+    #[case::holesky_post_genesis(KnownNetwork::Holesky, 1, false)]
+    fn london_activation(
+        #[case] network: KnownNetwork,
+        #[case] block_number: u64,
+        #[case] expected: bool,
+    ) {
+        let header = Header {
+            number: block_number,
+            ..Default::default()
+        };
+        assert_eq!(is_london_activation(&header, network), expected);
+    }
+
+    #[test]
+    fn short_id_of_header() {
+        let header = get_header(14_764_013);
+        assert_eq!(short_id(&header), "14764013@0x720704f3");
+        assert_eq!(short_id(&header), short_id(&header.clone()));
+    }
+
+    #[test]
+    fn burnt_fees_of_london_block() {
+        let header = get_header(14_764_013);
+        assert_eq!(
+            burnt_fees(&header),
+            Some(U256::from(150_596_842_974_657_750_u64))
+        );
+
+        let header = Header {
+            base_fee_per_gas: Some(u64::MAX),
+            gas_used: u64::MAX,
+            ..header
+        };
+        assert_eq!(
+            burnt_fees(&header),
+            Some(U256::from(u64::MAX) * U256::from(u64::MAX))
+        );
+
+        let pre_london_header = Header {
+            base_fee_per_gas: None,
+            ..header
+        };
+        assert_eq!(burnt_fees(&pre_london_header), None);
+    }
+
+    #[test]
+    fn nonce_bytes_with_leading_zero_bytes() {
+        let header = get_header(14_764_013);
+        assert_eq!(
+            nonce_bytes(&header),
+            Some([0x20, 0x87, 0xfb, 0xb2, 0x43, 0x32, 0x76, 0x96])
+        );
+
+        let nonce = [0x00, 0x00, 0x00, 0x2a, 0x43, 0x32, 0x76, 0x96];
+        let header = Header {
+            nonce: B64::from(nonce),
+            ..header
+        };
+        assert_eq!(nonce_bytes(&header), Some(nonce));
+
+        // The nonce is encoded as a fixed 8-byte string, right before the base fee
+        let rlp = alloy::rlp::encode(&header);
+        let mut nonce_rlp = vec![0x88];
+        nonce_rlp.extend_from_slice(&nonce);
+        nonce_rlp.extend(alloy::rlp::encode(header.base_fee_per_gas.unwrap()));
+        assert!(rlp.ends_with(&nonce_rlp));
+        let decoded = decode_header_rlp_strict(&rlp).unwrap();
+        assert_eq!(nonce_bytes(&decoded), Some(nonce));
+
+        let post_merge_header = get_header(17_034_871);
+        assert_eq!(nonce_bytes(&post_merge_header), None);
+    }
+
+    #[test]
+    fn blob_count_of_cancun_block() {
+        // The block has 3 blob transactions with one blob each
+        let header = get_header(19_433_903);
+        assert_eq!(blob_count(&header), Some(3));
+
+        assert_eq!(blob_count(&get_header(19_433_902)), Some(0));
+        assert_eq!(blob_count(&get_header(17_034_871)), None);
+
+        let header = Header {
+            blob_gas_used: Some(DATA_GAS_PER_BLOB * 3 + 1),
+            ..header
+        };
+        assert_eq!(blob_count(&header), None);
+    }
+
+    #[test]
+    fn bloom_contains_log_address_and_topic() {
+        let header = get_header(19_433_903);
+        // WETH and its Deposit event, which are in the logs of the block
+        let weth = alloy::primitives::address!("c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2");
+        let deposit_topic = alloy::primitives::b256!(
+            "e1fffcc4923d04b559f4d29a8bfc6cda04eb5b0d3c460751c2402c5c5cc9109c"
+        );
+        assert!(bloom_contains(&header, weth.as_slice()));
+        assert!(bloom_contains(&header, deposit_topic.as_slice()));
+
+        let absent = alloy::primitives::address!("0000000000000000000000000000000000000001");
+        assert!(!bloom_contains(&header, absent.as_slice()));
+    }
+
+    #[rstest]
+    #[case::frontier(1_000_000, Some(5))]
+    #[case::pre_byzantium(BYZANTIUM_BLOCK_NUMBER - 1, Some(5))]
+    #[case::byzantium(BYZANTIUM_BLOCK_NUMBER, Some(3))]
+    #[case::pre_constantinople(CONSTANTINOPLE_BLOCK_NUMBER - 1, Some(3))]
+    #[case::constantinople(CONSTANTINOPLE_BLOCK_NUMBER, Some(2))]
+    #[case::terminal_pow_block(MERGE_BLOCK_NUMBER - 1, Some(2))]
+    #[case::merge(MERGE_BLOCK_NUMBER, None)]
+    fn block_reward_at_fork(#[case] block_number: u64, #[case] expected_eth: Option<u64>) {
+        let header = Header {
+            number: block_number,
+            ..Default::default()
+        };
+        let expected =
+            expected_eth.map(|eth| U256::from(eth) * U256::from(1_000_000_000_000_000_000_u64));
+        assert_eq!(block_reward(&header), expected);
+    }
+
+    #[rstest]
+    #[case::frontier(1_000_000, Some(0))]
+    #[case::pre_muir_glacier(9_199_999, Some(5_000_000))]
+    #[case::muir_glacier(9_200_000, Some(9_000_000))]
+    #[case::london(LONDON_BLOCK_NUMBER, Some(9_700_000))]
+    #[case::arrow_glacier(13_773_000, Some(10_700_000))]
+    #[case::gray_glacier(15_050_000, Some(11_400_000))]
+    #[case::merge(MERGE_BLOCK_NUMBER, None)]
+    fn difficulty_bomb_delay_at_fork(#[case] block_number: u64, #[case] expected: Option<u64>) {
+        let header = Header {
+            number: block_number,
+            ..Default::default()
+        };
+        assert_eq!(difficulty_bomb_delay(&header), expected);
+    }
+
+    #[rstest]
+    #[case::capella(17_034_871, ForkName::Capella)]
+    #[case::deneb(19_433_902, ForkName::Deneb)]
+    fn execution_payload_header_round_trip(#[case] block_number: u64, #[case] fork: ForkName) {
+        let header = get_header(block_number);
+        let execution_payload_header = header_to_execution_payload_header(&header, fork).unwrap();
+        assert!(header_matches_execution_payload_header(
+            &header,
+            &execution_payload_header
+        ));
+
+        let other_header = Header {
+            gas_used: header.gas_used + 1,
+            ..header
+        };
+        assert!(!header_matches_execution_payload_header(
+            &other_header,
+            &execution_payload_header
+        ));
+    }
+
+    #[test]
+    fn execution_payload_header_of_other_fork() {
+        let header = get_header(17_034_871);
+        assert_eq!(
+            header_to_execution_payload_header(&header, ForkName::Deneb).unwrap_err(),
+            ConversionError::InvalidFieldsForFork {
+                block_number: 17_034_871,
+                fork: ForkName::Deneb,
+            }
+        );
+
+        let deneb_header =
+            header_to_execution_payload_header(&get_header(19_433_902), ForkName::Deneb).unwrap();
+        assert!(!header_matches_execution_payload_header(
+            &header,
+            &deneb_header
+        ));
+    }
+
+    #[test]
+    fn execution_payload_header_of_pre_merge_header() {
+        assert_eq!(
+            header_to_execution_payload_header(&get_header(14_764_013), ForkName::Bellatrix)
+                .unwrap_err(),
+            ConversionError::PreMergeHeader {
+                block_number: 14_764_013
+            }
+        );
+    }
+
+    #[cfg(feature = "custom-header-hash")]
+    #[test]
+    fn header_hash_with_custom_hasher() {
+        /// Hashes with sha256, like some test chains.
+        struct Sha256;
+
+        impl HashFn for Sha256 {
+            fn hash(&self, data: &[u8]) -> B256 {
+                B256::from_slice(&ethereum_hashing::hash(data))
+            }
+        }
+
+        let header = get_header(14_764_013);
+        assert_eq!(header_hash_with(&header, &Keccak256), header.hash_slow());
+
+        let hash = header_hash_with(&header, &Sha256);
+        assert_ne!(hash, header.hash_slow());
+        assert_eq!(
+            hash,
+            B256::from_slice(&ethereum_hashing::hash(&alloy::rlp::encode(&header)))
+        );
+    }
+
+    #[cfg(feature = "private-chains")]
+    #[test]
+    fn extra_data_chain_hint_of_stamped_header() {
+        let stamped_extra_data = |prefix: &[u8], chain_id: u64| {
+            Bytes::from([prefix, b"cid:", &chain_id.to_le_bytes()].concat())
+        };
+        let header = Header {
+            extra_data: stamped_extra_data(b"private chain", 1_337),
+            ..Default::default()
+        };
+        assert_eq!(extra_data_chain_hint(&header), Some(1_337));
+
+        let header = Header {
+            extra_data: stamped_extra_data(&[], u64::MAX),
+            ..Default::default()
+        };
+        assert_eq!(extra_data_chain_hint(&header), Some(u64::MAX));
+
+        // Mainnet headers aren't stamped
+        assert_eq!(extra_data_chain_hint(&get_header(14_764_013)), None);
+        let header = Header {
+            extra_data: Bytes::from(b"cid:1337".to_vec()),
+            ..Default::default()
+        };
+        assert_eq!(extra_data_chain_hint(&header), None);
+    }
+
+    #[cfg(feature = "erigon-interop")]
+    #[rstest]
+    #[case::pre_merge(14_764_013)]
+    #[case::post_shanghai(19_433_902)]
+    fn erigon_snapshot_round_trip(#[case] block_number: u64) {
+        let header = get_header(block_number);
+        let bytes = header_to_erigon_snapshot_bytes(&header);
+
+        // The record is the first byte of the block hash, followed by the RLP encoded header
+        assert_eq!(bytes[0], header.hash_slow()[0]);
+        assert_eq!(bytes[1..], alloy::rlp::encode(&header));
+        assert_eq!(header_from_erigon_snapshot_bytes(&bytes), Ok(header));
+    }
+
+    #[cfg(feature = "erigon-interop")]
+    #[test]
+    fn erigon_snapshot_invalid_record() {
+        let header = get_header(14_764_013);
+        let mut bytes = header_to_erigon_snapshot_bytes(&header);
+
+        assert_eq!(
+            header_from_erigon_snapshot_bytes(&[]),
+            Err(ErigonSnapshotError::EmptyRecord)
+        );
+        assert!(matches!(
+            header_from_erigon_snapshot_bytes(&bytes[..100]),
+            Err(ErigonSnapshotError::InvalidRlp(_))
+        ));
+
+        bytes[0] = bytes[0].wrapping_add(1);
+        assert_eq!(
+            header_from_erigon_snapshot_bytes(&bytes),
+            Err(ErigonSnapshotError::HashPrefixMismatch {
+                record_prefix: bytes[0],
+                hash_prefix: header.hash_slow()[0],
+            })
+        );
+    }
+
+    #[test]
+    fn beacon_roots_ring_index_of_cancun_block() {
+        assert_eq!(BEACON_ROOTS_HISTORY_BUFFER_LENGTH, 8191);
+        // Timestamp of the first Cancun block 19426587
+        assert_eq!(beacon_roots_ring_index(1_710_338_135), 8189);
+        assert_eq!(
+            beacon_roots_ring_index(1_710_338_135 + BEACON_ROOTS_HISTORY_BUFFER_LENGTH),
+            8189
+        );
+    }
+
+    #[test]
+    fn age_in_slots_of_shanghai_block() {
+        // Block 17034871 was proposed in slot 6209540
+        let header = get_header(17_034_871);
+        let age = |current_slot| {
+            age_in_slots(
+                &header,
+                current_slot,
+                MAINNET_BEACON_GENESIS_TIME,
+                MAINNET_SECONDS_PER_SLOT,
+            )
+        };
+        assert_eq!(age(6_209_540), 0);
+        assert_eq!(age(6_209_640), 100);
+        assert_eq!(age(6_209_530), -10);
+    }
+
+    #[test]
+    fn slot_of_shanghai_block() {
+        // The first Shanghai block 17034870 was proposed in slot 6209536
+        assert_eq!(mainnet_slot_from_timestamp(1_681_338_455), 6_209_536);
+        assert_eq!(mainnet_timestamp_at_slot(6_209_536), 1_681_338_455);
+        // The first slots of Deneb on Sepolia and Holesky
+        assert_eq!(
+            timestamp_at_slot(4_243_456, KnownNetwork::Sepolia),
+            1_706_655_072
+        );
+        assert_eq!(
+            timestamp_at_slot(950_272, KnownNetwork::Holesky),
+            1_707_305_664
+        );
+        assert_eq!(
+            slot_from_timestamp(1_681_338_455 + 11, MAINNET_BEACON_GENESIS_TIME, 12),
+            6_209_536
+        );
+        assert_eq!(
+            slot_from_timestamp(1_000, MAINNET_BEACON_GENESIS_TIME, 12),
+            0
+        );
+    }
+
+    #[test]
+    fn next_block_base_fee_at_london_activation() {
+        // The parent of the London activation block doesn't have a base fee
+        let parent = Header {
+            number: LONDON_BLOCK_NUMBER - 1,
+            gas_limit: 30_000_000,
+            ..Default::default()
+        };
+        assert_eq!(parent.next_block_base_fee(BaseFeeParams::ethereum()), None);
+        assert_eq!(
+            super::next_block_base_fee(&parent, KnownNetwork::Mainnet, BaseFeeParams::ethereum()),
+            Some(INITIAL_BASE_FEE)
+        );
+
+        let header = get_header(19_433_902);
+        let next_header = get_header(19_433_903);
+        assert_eq!(
+            super::next_block_base_fee(&header, KnownNetwork::Mainnet, BaseFeeParams::ethereum()),
+            next_header.base_fee_per_gas
+        );
+
+        // There is no block after the last block number
+        let header = Header {
+            number: u64::MAX,
+            ..header
+        };
+        assert_eq!(
+            super::next_block_base_fee(&header, KnownNetwork::Mainnet, BaseFeeParams::ethereum()),
+            None
+        );
+    }
+
+    #[test]
+    fn blob_base_fee_series_cancun_range() {
+        let headers = [
+            get_header(14_764_013),
+            get_header(19_433_902),
+            get_header(19_433_903),
+        ];
+
+        // Blob base fee is at its minimum of 1 wei while the excess blob gas is zero
+        assert_eq!(
+            blob_base_fee_series(&headers),
+            vec![None, Some(U256::from(1)), Some(U256::from(1))]
+        );
+        assert_eq!(
+            headers[1].next_block_excess_blob_gas(BlobParams::cancun()),
+            headers[2].excess_blob_gas
+        );
+    }
+
+    #[test]
+    fn blob_fee_burnt_of_cancun_block() {
+        // The block has 3 blobs, at the minimum blob base fee of 1 wei
+        let header = get_header(19_433_903);
+        assert_eq!(blob_fee_burnt(&header), Some(U256::from(3 * 131_072)));
+        assert_eq!(blob_fee_burnt(&get_header(19_433_902)), Some(U256::ZERO));
+        assert_eq!(blob_fee_burnt(&get_header(17_034_871)), None);
+
+        // The blob base fee is 23 wei with this excess blob gas
+        let header = Header {
+            excess_blob_gas: Some(10 * 1024 * 1024),
+            ..header
+        };
+        assert_eq!(blob_fee_burnt(&header), Some(U256::from(23 * 3 * 131_072)));
+    }
+
+    // The blob base fee doubles once the excess blob gas reaches `ln(2)` times the update
+    // fraction, which is 3338477 for Cancun and 5007716 for Prague.
+    #[rstest]
+    #[case::cancun_minimum(CANCUN_TIMESTAMP, 2_314_057, 1)]
+    #[case::cancun_first_increase(CANCUN_TIMESTAMP, 2_314_058, 2)]
+    #[case::cancun(CANCUN_TIMESTAMP, 10 * 1024 * 1024, 23)]
+    #[case::prague_minimum(PRAGUE_TIMESTAMP, 3_471_086, 1)]
+    #[case::prague_first_increase(PRAGUE_TIMESTAMP, 3_471_087, 2)]
+    #[case::prague(PRAGUE_TIMESTAMP, 10 * 1024 * 1024, 8)]
+    fn blob_base_fee_series_excess_blob_gas(
+        #[case] timestamp: u64,
+        #[case] excess_blob_gas: u64,
+        #[case] expected: u64,
+    ) {
+        let header = Header {
+            timestamp,
+            excess_blob_gas: Some(excess_blob_gas),
+            ..Default::default()
+        };
+        assert_eq!(
+            blob_base_fee_series(&[header]),
+            vec![Some(U256::from(expected))]
+        );
+    }
+
+    #[rstest]
+    #[case::london(14_764_013)]
+    #[case::shanghai(17_034_871)]
+    #[case::cancun(19_433_902)]
+    fn fixed_layout_round_trip(#[case] block_number: u64) {
+        let header = get_header(block_number);
+        let fixed_layout = header_to_fixed_layout(&header).unwrap();
+        assert_eq!(header_from_fixed_layout(&fixed_layout).unwrap(), header);
+    }
+
+    #[test]
+    fn fixed_layout_size() {
+        // The size is part of the storage format, so it must not change
+        assert_eq!(FIXED_HEADER_LAYOUT_SIZE, 694);
+
+        let header = Header {
+            extra_data: Bytes::from(vec![1; 32]),
+            requests_hash: Some(B256::random()),
+            ..Default::default()
+        };
+        let fixed_layout = header_to_fixed_layout(&header).unwrap();
+        assert_eq!(header_from_fixed_layout(&fixed_layout).unwrap(), header);
+    }
+
+    #[test]
+    fn fixed_layout_rejects_long_extra_data() {
+        let header = Header {
+            extra_data: Bytes::from(vec![1; 33]),
+            ..Default::default()
+        };
+        assert_eq!(header_to_fixed_layout(&header), None);
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn arbitrary_header_rlp_round_trip() {
+        use arbitrary::Unstructured;
+        use rand::RngCore;
+
+        let mut bytes = vec![0u8; 1024];
+        for _ in 0..100 {
+            rand::thread_rng().fill_bytes(&mut bytes);
+            let header = arbitrary_header(&mut Unstructured::new(&bytes)).unwrap();
+
+            let rlp = alloy::rlp::encode(&header);
+            let decoded = decode_header_rlp_strict(&rlp).unwrap();
+            assert_eq!(decoded, header);
+        }
+    }
+
+    /// Checks that the RLP encode -> decode -> encode cycle is idempotent and that the hash is
+    /// stable, for headers with the fields of random forks.
+    ///
+    /// The header is generated from the random bytes, so quickcheck shrinks a failing case by
+    /// shrinking the bytes.
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn quickcheck_arbitrary_header_rlp_idempotent() {
+        use arbitrary::Unstructured;
+        use quickcheck::{Gen, QuickCheck, TestResult};
+
+        fn prop(bytes: Vec<u8>) -> TestResult {
+            let Ok(header) = arbitrary_header(&mut Unstructured::new(&bytes)) else {
+                return TestResult::discard();
+            };
+
+            let rlp = alloy::rlp::encode(&header);
+            let Ok(decoded) = decode_header_rlp_strict(&rlp) else {
+                return TestResult::failed();
+            };
+            let hash = header.hash_slow();
+            TestResult::from_bool(
+                alloy::rlp::encode(&decoded) == rlp
+                    && decoded.hash_slow() == hash
+                    && header.clone().hash_slow() == hash
+                    && alloy::primitives::keccak256(&rlp) == hash,
+            )
+        }
+        // Large enough inputs for all the fields of the header to be random
+        QuickCheck::new()
+            .gen(Gen::new(2048))
+            .quickcheck(prop as fn(Vec<u8>) -> TestResult);
+    }
+}
@@ -1,9 +1,15 @@
-use alloy::{consensus::Header, primitives::B256};
+use alloy::{
+    consensus::Header,
+    primitives::{Bytes, B256},
+};
 use jsonrpsee::core::Serialize;
+#[cfg(feature = "parallel-proofs")]
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use serde::Deserialize;
 use ssz::SszDecoderBuilder;
 use ssz_derive::{Decode, Encode};
 use ssz_types::{typenum, FixedVector, VariableList};
+use thiserror::Error;
 use tree_hash::TreeHash;
 
 use crate::types::{
@@ -11,9 +17,12 @@ use crate::types::{
     consensus::{
         beacon_block::{BeaconBlockBellatrix, BeaconBlockCapella},
         beacon_state::{BeaconStateCapella, HistoricalBatch},
+        fork::ForkName,
         proof::build_merkle_proof_for_index,
     },
+    content_key::{history::HistoryContentKey, overlay::OverlayContentKey},
     execution::{
+        beacon_block_proof_cache::BeaconBlockProofCache,
         block_body::{MERGE_TIMESTAMP, SHANGHAI_TIMESTAMP},
         ssz_header,
     },
@@ -26,7 +35,8 @@ pub type BlockProofHistoricalHashesAccumulator = FixedVector<B256, typenum::U15>
 /// for TheMerge until Capella
 pub type ExecutionBlockProof = FixedVector<B256, typenum::U11>;
 /// Proof that EL block_hash is in BeaconBlock -> BeaconBlockBody -> ExecutionPayload
-/// for Post-Capella
+/// for Post-Capella. It has 11 hashes until Deneb, and 12 hashes from Deneb, where the
+/// ExecutionPayload has more than 16 fields.
 pub type ExecutionBlockProofCapella = VariableList<B256, typenum::U12>;
 /// Proof that BeaconBlock root is part of historical_summaries and thus canonical
 /// for Capella and onwards
@@ -35,6 +45,56 @@ pub type BeaconBlockProofHistoricalSummaries = FixedVector<B256, typenum::U13>;
 /// from TheMerge until Capella -> Bellatrix fork.
 pub type BeaconBlockProofHistoricalRoots = FixedVector<B256, typenum::U14>;
 
+/// An error verifying a `BlockHeaderProof`.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ProofError {
+    /// The beacon block is more recent than the latest slot covered by the
+    /// `historical_summaries`, so the proof can't be verified yet.
+    #[error("slot {slot} of historical summaries period {period} is not in the historical summaries yet, latest summarized slot: {latest_summarized_slot}")]
+    NotYetFinalized {
+        slot: u64,
+        period: u64,
+        latest_summarized_slot: u64,
+    },
+    /// The slot of the proof is before Capella, so it isn't part of any `historical_summaries`
+    /// period.
+    #[error("slot {slot} is before Capella and has no historical summaries period")]
+    PreCapellaSlot { slot: u64 },
+    /// The proof type doesn't match the fork of the header.
+    #[error("proof type doesn't match the fork of block {block_number}")]
+    UnexpectedProofType { block_number: u64 },
+    /// The root of the beacon block at the slot of the proof couldn't be resolved.
+    #[error("beacon block root of slot {slot} is not available")]
+    UnknownBeaconBlockRoot { slot: u64 },
+    /// The proof that the pre-merge header is part of the pre-merge accumulator is invalid.
+    #[error("Merkle proof validation failed for pre-merge header")]
+    InvalidHistoricalHashesProof,
+    /// The `historical_roots` entry needed to verify the proof is not available.
+    #[error("historical root {index} is not available")]
+    MissingHistoricalRoot { index: u64 },
+    /// The proof that the beacon block is part of the `historical_roots` is invalid.
+    #[error("Merkle proof validation failed for HistoricalRootsProof")]
+    InvalidHistoricalRootsProof,
+    /// The proof that the execution block header is part of the beacon block is invalid.
+    #[error("Merkle proof validation failed for BeaconBlockProof")]
+    InvalidBeaconBlockProof,
+    /// The proof that the execution block header is part of the beacon block has invalid length.
+    #[error("invalid execution block proof length: {length}")]
+    InvalidExecutionBlockProofLength { length: usize },
+    /// The proof that the beacon block is part of the `historical_summaries` is invalid.
+    #[error("Merkle proof validation failed for HistoricalSummariesProof")]
+    InvalidHistoricalSummariesProof,
+    /// The slot of the beacon block doesn't match the slot the proof is built for.
+    #[error("beacon block is at slot {beacon_block_slot}, but the proof is for slot {slot}")]
+    SlotMismatch { slot: u64, beacon_block_slot: u64 },
+    /// The combined branch of a compact proof has an invalid length.
+    #[error("invalid compact proof branch length: {length}")]
+    InvalidCompactProofLength { length: usize },
+    /// The proof couldn't be decoded from the legacy Nimbus layout.
+    #[error("invalid legacy proof encoding: {0}")]
+    InvalidLegacyEncoding(String),
+}
+
 /// A block header with accumulator proof.
 /// Type definition:
 /// https://github.com/status-im/nimbus-eth1/blob/master/fluffy/network/history/history_content.nim#L136
@@ -45,7 +105,75 @@ pub struct HeaderWithProof {
     pub proof: BlockHeaderProof,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+impl HeaderWithProof {
+    /// Returns the content id of the header, which only depends on the block hash and not on the
+    /// proof.
+    ///
+    /// The same header can be received with different proofs (e.g. a `historical_roots` and a
+    /// `historical_summaries` proof), so storage should be keyed on this id, which allows
+    /// replacing the proof of an already stored header with an upgraded one.
+    pub fn canonical_content_id(&self) -> [u8; 32] {
+        HistoryContentKey::new_block_header_by_hash(self.header.hash_slow()).content_id()
+    }
+
+    /// Decodes a header with proof that was stored with the given version of the encoding,
+    /// upgrading it to the current encoding.
+    ///
+    /// This allows reading the content stored before the encoding changed, without downloading it
+    /// again.
+    pub fn from_stored_bytes(bytes: &[u8], stored_version: u8) -> Result<Self, ssz::DecodeError> {
+        use ssz::Decode;
+
+        match stored_version {
+            LEGACY_UNION_STORED_VERSION => Self::from_legacy_union_bytes(bytes),
+            CURRENT_STORED_VERSION => Self::from_ssz_bytes(bytes),
+            _ => Err(ssz::DecodeError::BytesInvalid(format!(
+                "Unknown stored version of header with proof: {stored_version}"
+            ))),
+        }
+    }
+
+    /// Decodes a header with proof from the legacy encoding, where the proof is an SSZ union with
+    /// a selector per proof type, instead of being inferred from the timestamp of the header.
+    fn from_legacy_union_bytes(bytes: &[u8]) -> Result<Self, ssz::DecodeError> {
+        use ssz::Decode;
+
+        let mut builder = SszDecoderBuilder::new(bytes);
+
+        builder.register_anonymous_variable_length_item()?;
+        builder.register_anonymous_variable_length_item()?;
+
+        let mut decoder = builder.build()?;
+
+        let header = decoder.decode_next_with(ssz_header::decode::from_ssz_bytes)?;
+        let proof = decoder.decode_next_with(|bytes| {
+            let (selector, proof) = ssz::split_union_bytes(bytes)?;
+            match selector.into() {
+                1 => Ok(BlockHeaderProof::HistoricalHashes(
+                    BlockProofHistoricalHashesAccumulator::from_ssz_bytes(proof)?,
+                )),
+                2 => Ok(BlockHeaderProof::HistoricalRoots(
+                    BlockProofHistoricalRoots::from_ssz_bytes(proof)?,
+                )),
+                3 => Ok(BlockHeaderProof::HistoricalSummaries(
+                    BlockProofHistoricalSummaries::from_ssz_bytes(proof)?,
+                )),
+                // The legacy `None` proof (selector 0) can't be upgraded
+                selector => Err(ssz::DecodeError::UnionSelectorInvalid(selector)),
+            }
+        })?;
+        Ok(Self { header, proof })
+    }
+}
+
+/// Version of the stored encoding of [HeaderWithProof], where the proof is an SSZ union.
+pub const LEGACY_UNION_STORED_VERSION: u8 = 1;
+
+/// Version of the current stored encoding of [HeaderWithProof], where the proof type is inferred
+/// from the timestamp of the header.
+pub const CURRENT_STORED_VERSION: u8 = 2;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
 pub enum BlockHeaderProof {
     // Pre-Merge
     HistoricalHashes(BlockProofHistoricalHashesAccumulator),
@@ -61,6 +189,39 @@ impl ssz::Decode for HeaderWithProof {
     }
 
     fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, ssz::DecodeError> {
+        Self::decode_with_proof_bytes(bytes).map(|(header_with_proof, _)| header_with_proof)
+    }
+}
+
+impl HeaderWithProof {
+    /// Decodes the header with proof, also returning the raw SSZ bytes of its proof.
+    ///
+    /// Together with [Self::encode_with_proof_bytes], this allows re-serving the content with the
+    /// exact proof bytes it was received with, so a drift in the encoding of the proof can't
+    /// change the served content.
+    pub fn decode_preserving_proof_bytes(bytes: &[u8]) -> Result<(Self, Bytes), ssz::DecodeError> {
+        Self::decode_with_proof_bytes(bytes)
+            .map(|(header_with_proof, proof)| (header_with_proof, Bytes::from(proof.to_vec())))
+    }
+
+    /// Encodes the header with the given raw SSZ bytes of its proof, instead of re-encoding the
+    /// decoded proof.
+    pub fn encode_with_proof_bytes(&self, proof_bytes: &[u8]) -> Vec<u8> {
+        let header_len = ssz_header::encode::ssz_bytes_len(&self.header);
+        let offset = 2 * ssz::BYTES_PER_LENGTH_OFFSET;
+        let mut buf = Vec::with_capacity(offset + header_len + proof_bytes.len());
+        let mut encoder = ssz::SszEncoder::container(&mut buf, offset);
+        encoder.append_parameterized(false, |buf| {
+            ssz_header::encode::ssz_append(&self.header, buf)
+        });
+        encoder.append_parameterized(false, |buf| buf.extend_from_slice(proof_bytes));
+        encoder.finalize();
+        buf
+    }
+
+    fn decode_with_proof_bytes(bytes: &[u8]) -> Result<(Self, ByteList1024), ssz::DecodeError> {
+        use ssz::Decode;
+
         let mut builder = SszDecoderBuilder::new(bytes);
 
         builder.register_anonymous_variable_length_item()?;
@@ -69,19 +230,21 @@ impl ssz::Decode for HeaderWithProof {
         let mut decoder = builder.build()?;
 
         let header = decoder.decode_next_with(ssz_header::decode::from_ssz_bytes)?;
-        let proof = decoder.decode_next::<ByteList1024>()?;
+        let proof_bytes = decoder.decode_next::<ByteList1024>()?;
         let proof = if header.timestamp <= MERGE_TIMESTAMP {
             BlockHeaderProof::HistoricalHashes(
-                BlockProofHistoricalHashesAccumulator::from_ssz_bytes(&proof)?,
+                BlockProofHistoricalHashesAccumulator::from_ssz_bytes(&proof_bytes)?,
             )
         } else if header.timestamp <= SHANGHAI_TIMESTAMP {
-            BlockHeaderProof::HistoricalRoots(BlockProofHistoricalRoots::from_ssz_bytes(&proof)?)
+            BlockHeaderProof::HistoricalRoots(BlockProofHistoricalRoots::from_ssz_bytes(
+                &proof_bytes,
+            )?)
         } else {
             BlockHeaderProof::HistoricalSummaries(BlockProofHistoricalSummaries::from_ssz_bytes(
-                &proof,
+                &proof_bytes,
             )?)
         };
-        Ok(Self { header, proof })
+        Ok((Self { header, proof }, proof_bytes))
     }
 }
 
@@ -118,7 +281,7 @@ impl ssz::Encode for BlockHeaderProof {
 /// beacon chain `historical_roots`.
 ///
 /// Proof for EL BlockHeader from TheMerge until Capella
-#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Encode, Decode, Serialize, Deserialize)]
 pub struct BlockProofHistoricalRoots {
     /// Proof that the BeaconBlock is part of the historical roots
     /// and thus part of the canonical chain.
@@ -136,7 +299,7 @@ pub struct BlockProofHistoricalRoots {
 /// beacon chain `historical_summaries`.
 ///
 /// Proof for EL BlockHeader for Capella and onwards
-#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Encode, Decode, Serialize, Deserialize)]
 pub struct BlockProofHistoricalSummaries {
     /// Proof that the BeaconBlock is part of the historical_summaries
     /// and thus part of the canonical chain.
@@ -149,11 +312,83 @@ pub struct BlockProofHistoricalSummaries {
     pub slot: u64,
 }
 
+/// Proof for EL BlockHeader from TheMerge until Capella, in the layout used by early versions of
+/// Fluffy (Nimbus).
+///
+/// The proof that the EL BlockHash is part of the BeaconBlock is split in two, with the
+/// intermediate BeaconBlockBody root in between.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+struct LegacyBlockProofHistoricalRoots {
+    /// Proof that EL BlockHash is part of the BeaconBlockBody
+    beacon_block_body_proof: FixedVector<B256, typenum::U8>,
+    beacon_block_body_root: B256,
+    /// Proof that the BeaconBlockBody is part of the BeaconBlock
+    beacon_block_header_proof: FixedVector<B256, typenum::U3>,
+    beacon_block_header_root: B256,
+    /// Proof that the BeaconBlock is part of the historical roots
+    historical_roots_proof: BeaconBlockProofHistoricalRoots,
+    slot: u64,
+}
+
+impl From<LegacyBlockProofHistoricalRoots> for BlockProofHistoricalRoots {
+    fn from(legacy: LegacyBlockProofHistoricalRoots) -> Self {
+        let execution_block_proof: Vec<B256> = legacy
+            .beacon_block_body_proof
+            .iter()
+            .chain(legacy.beacon_block_header_proof.iter())
+            .copied()
+            .collect();
+        Self {
+            beacon_block_proof: legacy.historical_roots_proof,
+            beacon_block_root: legacy.beacon_block_header_root,
+            execution_block_proof: execution_block_proof.into(),
+            slot: legacy.slot,
+        }
+    }
+}
+
+/// Decodes a proof in the legacy Nimbus layout (see [LegacyBlockProofHistoricalRoots]) and
+/// converts it to the current [BlockProofHistoricalRoots].
+///
+/// The intermediate BeaconBlockBody root is dropped, as it's implied by the combined execution
+/// block proof, which is checked when the converted proof is verified.
+pub fn from_legacy_nimbus_bytes(bytes: &[u8]) -> Result<BlockProofHistoricalRoots, ProofError> {
+    use ssz::Decode;
+
+    let legacy = LegacyBlockProofHistoricalRoots::from_ssz_bytes(bytes)
+        .map_err(|err| ProofError::InvalidLegacyEncoding(format!("{err:?}")))?;
+    Ok(legacy.into())
+}
+
 pub fn build_historical_roots_proof(
     slot: u64,
     historical_batch: &HistoricalBatch,
     beacon_block: BeaconBlockBellatrix,
 ) -> BlockProofHistoricalRoots {
+    let beacon_block_root = beacon_block.tree_hash_root();
+    build_historical_roots_proof_with_beacon_block_root(
+        slot,
+        historical_batch,
+        &beacon_block,
+        beacon_block_root,
+    )
+}
+
+/// Same as [build_historical_roots_proof], but with the precomputed `hash_tree_root` of the
+/// beacon block.
+///
+/// The `beacon_block_root` is only checked against the beacon block in debug builds.
+pub fn build_historical_roots_proof_with_beacon_block_root(
+    slot: u64,
+    historical_batch: &HistoricalBatch,
+    beacon_block: &BeaconBlockBellatrix,
+    beacon_block_root: B256,
+) -> BlockProofHistoricalRoots {
+    debug_assert_eq!(
+        beacon_block_root,
+        beacon_block.tree_hash_root(),
+        "Precomputed beacon block root doesn't match the beacon block"
+    );
     let beacon_block_proof = historical_batch.build_block_root_proof(slot % 8192);
 
     // execution block proof
@@ -163,7 +398,7 @@ pub fn build_historical_roots_proof(
 
     BlockProofHistoricalRoots {
         beacon_block_proof: beacon_block_proof.into(),
-        beacon_block_root: beacon_block.tree_hash_root(),
+        beacon_block_root,
         execution_block_proof: execution_block_hash_proof.into(),
         slot,
     }
@@ -174,6 +409,30 @@ pub fn build_historical_summaries_proof(
     capella_state: &BeaconStateCapella,
     beacon_block: BeaconBlockCapella,
 ) -> BlockProofHistoricalSummaries {
+    let beacon_block_root = beacon_block.tree_hash_root();
+    build_historical_summaries_proof_with_beacon_block_root(
+        slot,
+        capella_state,
+        &beacon_block,
+        beacon_block_root,
+    )
+}
+
+/// Same as [build_historical_summaries_proof], but with the precomputed `hash_tree_root` of the
+/// beacon block.
+///
+/// The `beacon_block_root` is only checked against the beacon block in debug builds.
+pub fn build_historical_summaries_proof_with_beacon_block_root(
+    slot: u64,
+    capella_state: &BeaconStateCapella,
+    beacon_block: &BeaconBlockCapella,
+    beacon_block_root: B256,
+) -> BlockProofHistoricalSummaries {
+    debug_assert_eq!(
+        beacon_block_root,
+        beacon_block.tree_hash_root(),
+        "Precomputed beacon block root doesn't match the beacon block"
+    );
     // beacon block proof
     let block_root_proof = capella_state.build_block_root_proof(slot as usize % 8192);
     let beacon_block_proof: FixedVector<B256, typenum::U13> = block_root_proof.into();
@@ -185,12 +444,44 @@ pub fn build_historical_summaries_proof(
 
     BlockProofHistoricalSummaries {
         beacon_block_proof,
-        beacon_block_root: beacon_block.tree_hash_root(),
+        beacon_block_root,
         execution_block_proof: execution_block_hash_proof.into(),
         slot,
     }
 }
 
+/// Returns the size of the SSZ encoded proof for headers of the given fork.
+///
+/// Summing the estimates over a range of headers gives the total size of their proofs, before
+/// generating them.
+pub fn estimate_proof_size(fork: ForkName) -> usize {
+    use ssz::Encode;
+
+    match fork {
+        ForkName::Bellatrix => BlockProofHistoricalRoots {
+            beacon_block_proof: FixedVector::default(),
+            beacon_block_root: B256::ZERO,
+            execution_block_proof: FixedVector::default(),
+            slot: 0,
+        }
+        .ssz_bytes_len(),
+        ForkName::Capella | ForkName::Deneb => {
+            // The execution block proof has 11 hashes until Deneb, and 12 hashes from Deneb
+            let execution_block_proof_len = match fork {
+                ForkName::Deneb => 12,
+                _ => 11,
+            };
+            BlockProofHistoricalSummaries {
+                beacon_block_proof: FixedVector::default(),
+                beacon_block_root: B256::ZERO,
+                execution_block_proof: vec![B256::ZERO; execution_block_proof_len].into(),
+                slot: 0,
+            }
+            .ssz_bytes_len()
+        }
+    }
+}
+
 pub fn build_block_proof_historical_roots(
     slot: u64,
     historical_batch: HistoricalBatch,
@@ -217,7 +508,14 @@ pub fn build_block_proof_historical_summaries(
     // block roots fields from BeaconState
     block_roots: FixedVector<B256, typenum::U8192>,
     beacon_block: BeaconBlockCapella,
-) -> BlockProofHistoricalSummaries {
+) -> Result<BlockProofHistoricalSummaries, ProofError> {
+    if beacon_block.slot != slot {
+        return Err(ProofError::SlotMismatch {
+            slot,
+            beacon_block_slot: beacon_block.slot,
+        });
+    }
+
     // beacon block proof
     let leaves = block_roots
         .iter()
@@ -232,25 +530,188 @@ pub fn build_block_proof_historical_summaries(
     let body_root_proof = beacon_block.build_body_root_proof();
     execution_block_hash_proof.extend(body_root_proof);
 
-    BlockProofHistoricalSummaries {
+    Ok(BlockProofHistoricalSummaries {
         beacon_block_proof,
         beacon_block_root: beacon_block.tree_hash_root(),
         execution_block_proof: execution_block_hash_proof.into(),
         slot,
+    })
+}
+
+/// Same as [build_block_proof_historical_roots], but reuses the beacon block proof of the slot
+/// from the cache, if it was already built.
+pub fn build_block_proof_historical_roots_with_cache(
+    slot: u64,
+    historical_batch: &HistoricalBatch,
+    beacon_block: &BeaconBlockBellatrix,
+    cache: &mut BeaconBlockProofCache,
+) -> BlockProofHistoricalRoots {
+    let beacon_block_root = beacon_block.tree_hash_root();
+    // beacon block proof
+    let beacon_block_proof = cache
+        .get_or_insert_with(slot, beacon_block_root, || {
+            historical_batch.build_block_root_proof(slot % 8192)
+        })
+        .to_vec();
+
+    // execution block proof
+    let mut execution_block_hash_proof = beacon_block.body.build_execution_block_hash_proof();
+    let body_root_proof = beacon_block.build_body_root_proof();
+    execution_block_hash_proof.extend(body_root_proof);
+
+    BlockProofHistoricalRoots {
+        beacon_block_proof: beacon_block_proof.into(),
+        beacon_block_root,
+        execution_block_proof: execution_block_hash_proof.into(),
+        slot,
+    }
+}
+
+/// Same as [build_block_proof_historical_summaries], but reuses the beacon block proof of the
+/// slot from the cache, if it was already built.
+pub fn build_block_proof_historical_summaries_with_cache(
+    slot: u64,
+    // block roots fields from BeaconState
+    block_roots: &FixedVector<B256, typenum::U8192>,
+    beacon_block: &BeaconBlockCapella,
+    cache: &mut BeaconBlockProofCache,
+) -> Result<BlockProofHistoricalSummaries, ProofError> {
+    if beacon_block.slot != slot {
+        return Err(ProofError::SlotMismatch {
+            slot,
+            beacon_block_slot: beacon_block.slot,
+        });
     }
+    let beacon_block_root = beacon_block.tree_hash_root();
+
+    // beacon block proof
+    let beacon_block_proof = cache
+        .get_or_insert_with(slot, beacon_block_root, || {
+            let leaves = block_roots
+                .iter()
+                .map(|root| root.tree_hash_root().0)
+                .collect();
+            build_merkle_proof_for_index(leaves, slot as usize % 8192)
+        })
+        .to_vec();
+
+    // execution block proof
+    let mut execution_block_hash_proof = beacon_block.body.build_execution_block_hash_proof();
+    let body_root_proof = beacon_block.build_body_root_proof();
+    execution_block_hash_proof.extend(body_root_proof);
+
+    Ok(BlockProofHistoricalSummaries {
+        beacon_block_proof: beacon_block_proof.into(),
+        beacon_block_root,
+        execution_block_proof: execution_block_hash_proof.into(),
+        slot,
+    })
+}
+
+/// The inputs needed to build the proof of a post-merge header, by the fork of its beacon block.
+#[derive(Debug, Clone)]
+pub enum ProofRequest {
+    /// A Bellatrix beacon block, proven with the `historical_roots`.
+    HistoricalRoots {
+        slot: u64,
+        historical_batch: HistoricalBatch,
+        beacon_block: BeaconBlockBellatrix,
+    },
+    /// A Capella beacon block, proven with the `historical_summaries`.
+    HistoricalSummaries {
+        slot: u64,
+        block_roots: FixedVector<B256, typenum::U8192>,
+        beacon_block: BeaconBlockCapella,
+    },
+}
+
+impl ProofRequest {
+    /// Builds the proof with the builder of the fork of the request.
+    pub fn build(self) -> Result<BlockHeaderProof, ProofError> {
+        match self {
+            Self::HistoricalRoots {
+                slot,
+                historical_batch,
+                beacon_block,
+            } => Ok(BlockHeaderProof::HistoricalRoots(
+                build_block_proof_historical_roots(slot, historical_batch, beacon_block),
+            )),
+            Self::HistoricalSummaries {
+                slot,
+                block_roots,
+                beacon_block,
+            } => build_block_proof_historical_summaries(slot, block_roots, beacon_block)
+                .map(BlockHeaderProof::HistoricalSummaries),
+        }
+    }
+}
+
+/// Builds the proofs of the requests in parallel, returning the results in the order of the
+/// requests.
+///
+/// This is meant for reprocessing history that spans multiple forks, where each request is built
+/// with the builder of its fork.
+#[cfg(feature = "parallel-proofs")]
+pub fn build_proofs_parallel(
+    requests: Vec<ProofRequest>,
+) -> Vec<Result<BlockHeaderProof, ProofError>> {
+    requests.into_par_iter().map(ProofRequest::build).collect()
+}
+
+/// The roots that the chain of proofs of a [BlockProofHistoricalSummaries] reconstructs, recorded
+/// when the proof is built.
+///
+/// A consumer can check that the proof reconstructs these roots before trusting it, without
+/// access to the `historical_summaries`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerificationWitness {
+    /// The block hash of the execution block, which is the leaf of the execution block proof.
+    pub execution_block_hash: B256,
+    /// The root reconstructed by the execution block proof.
+    pub beacon_block_root: B256,
+    /// The root reconstructed by the beacon block proof, i.e. the block summary root of the
+    /// historical summaries period.
+    pub block_summary_root: B256,
+}
+
+/// Same as [build_block_proof_historical_summaries], but also returns the [VerificationWitness]
+/// of the proof.
+pub fn build_block_proof_historical_summaries_with_witness(
+    slot: u64,
+    // block roots fields from BeaconState
+    block_roots: FixedVector<B256, typenum::U8192>,
+    beacon_block: BeaconBlockCapella,
+) -> Result<(BlockProofHistoricalSummaries, VerificationWitness), ProofError> {
+    let execution_block_hash = beacon_block.body.execution_payload.block_hash;
+    let block_summary_root = block_roots.tree_hash_root();
+    let proof = build_block_proof_historical_summaries(slot, block_roots, beacon_block)?;
+    let witness = VerificationWitness {
+        execution_block_hash,
+        beacon_block_root: proof.beacon_block_root,
+        block_summary_root,
+    };
+    Ok((proof, witness))
 }
 
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod tests {
+    use std::collections::HashSet;
+
+    use rstest::rstest;
     use serde_json::Value;
     use serde_yaml::Value as YamlValue;
     use ssz::Decode;
 
     use super::*;
     use crate::{
-        test_utils::{read_bytes_from_tests_submodule, read_file_from_tests_submodule},
-        types::consensus::{beacon_state::BeaconState, fork::ForkName},
+        test_utils::{
+            bellatrix_block, capella_block, deneb_block, read_bytes_from_tests_submodule,
+            read_dir_from_tests_submodule, read_file_from_tests_submodule,
+        },
+        types::{
+            consensus::beacon_state::BeaconState, execution::header::mainnet_slot_from_timestamp,
+        },
         utils::bytes::{hex_decode, hex_encode},
     };
 
@@ -296,6 +757,51 @@ mod tests {
         assert_eq!(encoded, actual_hwp);
     }
 
+    /// Golden test of the SSZ encoding of all the headers with proof vectors of the spec tests,
+    /// which checks that every vector decodes and re-encodes to the same bytes.
+    #[test]
+    fn decode_encode_all_headers_with_proof_vectors() {
+        // The vector name, the block number of its header if known, and its content value
+        let mut vectors = vec![];
+        let dir = "tests/mainnet/history/headers_with_proof";
+        for (file_name, file) in read_dir_from_tests_submodule(dir, "json").unwrap() {
+            let json: Value = serde_json::from_str(&file).unwrap();
+            for (block_number, obj) in json.as_object().unwrap() {
+                let content_value = obj["content_value"].as_str().unwrap().to_string();
+                vectors.push((
+                    format!("{file_name}/{block_number}"),
+                    Some(block_number.parse::<u64>().unwrap()),
+                    content_value,
+                ));
+            }
+        }
+        for (file_name, file) in read_dir_from_tests_submodule(dir, "yaml").unwrap() {
+            let yaml: YamlValue = serde_yaml::from_str(&file).unwrap();
+            let content_value = yaml["content_value"].as_str().unwrap().to_string();
+            vectors.push((file_name, None, content_value));
+        }
+        assert!(
+            !vectors.is_empty(),
+            "no headers with proof vectors in {dir}"
+        );
+
+        for (vector, block_number, content_value) in vectors {
+            let hwp = HeaderWithProof::from_ssz_bytes(&hex_decode(&content_value).unwrap())
+                .unwrap_or_else(|err| panic!("failed to decode {vector}: {err:?}"));
+            if let Some(block_number) = block_number {
+                assert_eq!(
+                    hwp.header.number, block_number,
+                    "{vector} has unexpected header"
+                );
+            }
+            assert_eq!(
+                hex_encode(ssz::Encode::as_ssz_bytes(&hwp)),
+                content_value,
+                "re-encoded {vector} doesn't match"
+            );
+        }
+    }
+
     #[rstest::rstest]
     #[case(
         15539558,
@@ -386,8 +892,530 @@ mod tests {
         let block_raw =
             read_bytes_from_tests_submodule(format!("{test_assets_dir}/block.ssz",)).unwrap();
         let block = BeaconBlockCapella::from_ssz_bytes(&block_raw).unwrap();
-        let actual_proof = build_block_proof_historical_summaries(slot, block_roots, block);
+        let actual_proof =
+            build_block_proof_historical_summaries(slot, block_roots, block).unwrap();
 
         assert_eq!(expected_proof, actual_proof);
     }
+
+    #[test]
+    fn decode_encode_deneb_header_with_proof() {
+        use ssz::Encode;
+
+        let file =
+            std::fs::read_to_string("../../test_assets/mainnet/block_19433902_value.json").unwrap();
+        let json: Value = serde_json::from_str(&file).unwrap();
+        let header: Header = serde_json::from_value(json["result"].clone()).unwrap();
+        let slot = mainnet_slot_from_timestamp(header.timestamp);
+        let block = deneb_block(slot, header.hash_slow());
+
+        // The ExecutionPayload has more than 16 fields from Deneb, which adds a level to the
+        // execution block proof
+        let mut execution_block_proof = block.body.build_execution_block_hash_proof();
+        execution_block_proof.extend(block.build_body_root_proof());
+        assert_eq!(execution_block_proof.len(), 12);
+
+        let hwp = HeaderWithProof {
+            header,
+            proof: BlockHeaderProof::HistoricalSummaries(BlockProofHistoricalSummaries {
+                beacon_block_proof: vec![B256::random(); 13].into(),
+                beacon_block_root: block.tree_hash_root(),
+                execution_block_proof: execution_block_proof.into(),
+                slot,
+            }),
+        };
+        let encoded = hwp.as_ssz_bytes();
+        assert_eq!(HeaderWithProof::from_ssz_bytes(&encoded).unwrap(), hwp);
+    }
+
+    #[test]
+    fn historical_summaries_proof_with_mismatched_slot() {
+        let block = capella_block(6_209_538);
+        let block_roots: FixedVector<B256, typenum::U8192> = vec![B256::random(); 8192].into();
+
+        let proof =
+            build_block_proof_historical_summaries(6_209_538, block_roots.clone(), block.clone())
+                .unwrap();
+        assert_eq!(proof.slot, 6_209_538);
+        assert_eq!(
+            build_block_proof_historical_summaries(6_209_539, block_roots, block),
+            Err(ProofError::SlotMismatch {
+                slot: 6_209_539,
+                beacon_block_slot: 6_209_538,
+            })
+        );
+    }
+
+    #[test]
+    fn build_proofs_with_cache_reuse_beacon_block_proofs() {
+        let mut cache = BeaconBlockProofCache::new();
+
+        let block = capella_block(6_209_538);
+        let block_roots: FixedVector<B256, typenum::U8192> = vec![B256::random(); 8192].into();
+        let proof = build_block_proof_historical_summaries_with_cache(
+            6_209_538,
+            &block_roots,
+            &block,
+            &mut cache,
+        )
+        .unwrap();
+        assert_eq!(
+            proof,
+            build_block_proof_historical_summaries(6_209_538, block_roots, block.clone()).unwrap()
+        );
+        assert_eq!(cache.len(), 1);
+
+        // The cached beacon block proof is reused, rather than built from the new block roots
+        let other_block_roots: FixedVector<B256, typenum::U8192> =
+            vec![B256::random(); 8192].into();
+        let cached_proof = build_block_proof_historical_summaries_with_cache(
+            6_209_538,
+            &other_block_roots,
+            &block,
+            &mut cache,
+        )
+        .unwrap();
+        assert_eq!(cached_proof, proof);
+        assert_eq!(cache.len(), 1);
+
+        let block = bellatrix_block();
+        let historical_batch = HistoricalBatch {
+            block_roots: vec![B256::random(); 8192].into(),
+            state_roots: vec![B256::random(); 8192].into(),
+        };
+        let proof = build_block_proof_historical_roots_with_cache(
+            block.slot,
+            &historical_batch,
+            &block,
+            &mut cache,
+        );
+        assert_eq!(
+            proof,
+            build_block_proof_historical_roots(block.slot, historical_batch, block.clone())
+        );
+        let other_historical_batch = HistoricalBatch {
+            block_roots: vec![B256::random(); 8192].into(),
+            state_roots: vec![B256::random(); 8192].into(),
+        };
+        assert_eq!(
+            build_block_proof_historical_roots_with_cache(
+                block.slot,
+                &other_historical_batch,
+                &block,
+                &mut cache,
+            ),
+            proof
+        );
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "parallel-proofs")]
+    fn build_proofs_parallel_across_forks() {
+        let bellatrix_block = bellatrix_block();
+        let historical_batch = HistoricalBatch {
+            block_roots: vec![B256::random(); 8192].into(),
+            state_roots: vec![B256::random(); 8192].into(),
+        };
+        let block_roots: FixedVector<B256, typenum::U8192> = vec![B256::random(); 8192].into();
+        let requests = vec![
+            ProofRequest::HistoricalSummaries {
+                slot: 6_209_538,
+                block_roots: block_roots.clone(),
+                beacon_block: capella_block(6_209_538),
+            },
+            ProofRequest::HistoricalRoots {
+                slot: bellatrix_block.slot,
+                historical_batch: historical_batch.clone(),
+                beacon_block: bellatrix_block.clone(),
+            },
+            ProofRequest::HistoricalSummaries {
+                slot: 6_209_539,
+                block_roots: block_roots.clone(),
+                beacon_block: capella_block(6_209_538),
+            },
+            ProofRequest::HistoricalSummaries {
+                slot: 6_217_730,
+                block_roots: block_roots.clone(),
+                beacon_block: capella_block(6_217_730),
+            },
+        ];
+
+        let proofs = build_proofs_parallel(requests.clone());
+        assert_eq!(proofs.len(), requests.len());
+        assert_eq!(
+            proofs[0],
+            Ok(BlockHeaderProof::HistoricalSummaries(
+                build_block_proof_historical_summaries(
+                    6_209_538,
+                    block_roots.clone(),
+                    capella_block(6_209_538)
+                )
+                .unwrap()
+            ))
+        );
+        assert_eq!(
+            proofs[1],
+            Ok(BlockHeaderProof::HistoricalRoots(
+                build_block_proof_historical_roots(
+                    bellatrix_block.slot,
+                    historical_batch,
+                    bellatrix_block
+                )
+            ))
+        );
+        assert_eq!(
+            proofs[2],
+            Err(ProofError::SlotMismatch {
+                slot: 6_209_539,
+                beacon_block_slot: 6_209_538,
+            })
+        );
+        assert_eq!(
+            proofs[3],
+            Ok(BlockHeaderProof::HistoricalSummaries(
+                build_block_proof_historical_summaries(
+                    6_217_730,
+                    block_roots,
+                    capella_block(6_217_730)
+                )
+                .unwrap()
+            ))
+        );
+    }
+
+    #[test]
+    fn historical_roots_proof_with_precomputed_beacon_block_root() {
+        let block = bellatrix_block();
+        let historical_batch = HistoricalBatch {
+            block_roots: vec![B256::random(); 8192].into(),
+            state_roots: vec![B256::random(); 8192].into(),
+        };
+        let slot = block.slot;
+
+        let beacon_block_root = block.tree_hash_root();
+        let precomputed_proof = build_historical_roots_proof_with_beacon_block_root(
+            slot,
+            &historical_batch,
+            &block,
+            beacon_block_root,
+        );
+        let recomputed_proof = build_historical_roots_proof(slot, &historical_batch, block);
+
+        assert_eq!(precomputed_proof, recomputed_proof);
+    }
+
+    #[test]
+    fn historical_roots_proof_from_legacy_nimbus_bytes() {
+        use ssz::Encode;
+
+        let block = bellatrix_block();
+        let historical_batch = HistoricalBatch {
+            block_roots: vec![B256::random(); 8192].into(),
+            state_roots: vec![B256::random(); 8192].into(),
+        };
+        let legacy_proof = LegacyBlockProofHistoricalRoots {
+            beacon_block_body_proof: block.body.build_execution_block_hash_proof().into(),
+            beacon_block_body_root: block.body.tree_hash_root(),
+            beacon_block_header_proof: block.build_body_root_proof().into(),
+            beacon_block_header_root: block.tree_hash_root(),
+            historical_roots_proof: historical_batch
+                .build_block_root_proof(block.slot % 8192)
+                .into(),
+            slot: block.slot,
+        };
+        let legacy_bytes = legacy_proof.as_ssz_bytes();
+        assert_eq!(legacy_bytes.len(), 872);
+
+        let proof = from_legacy_nimbus_bytes(&legacy_bytes).unwrap();
+        assert_eq!(
+            proof,
+            build_historical_roots_proof(block.slot, &historical_batch, block)
+        );
+
+        assert!(matches!(
+            from_legacy_nimbus_bytes(&legacy_bytes[1..]),
+            Err(ProofError::InvalidLegacyEncoding(_))
+        ));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "Precomputed beacon block root doesn't match the beacon block")]
+    fn historical_roots_proof_with_invalid_precomputed_beacon_block_root() {
+        let block = bellatrix_block();
+        let historical_batch = HistoricalBatch {
+            block_roots: vec![B256::random(); 8192].into(),
+            state_roots: vec![B256::random(); 8192].into(),
+        };
+
+        build_historical_roots_proof_with_beacon_block_root(
+            block.slot,
+            &historical_batch,
+            &block,
+            B256::random(),
+        );
+    }
+
+    #[rstest::rstest]
+    #[case(17034870, 6209538)] // epoch 759
+    #[case(17042287, 6217730)] // epoch 760
+    #[case(17062257, 6238210)] // epoch 762
+    fn historical_summaries_proof_with_precomputed_beacon_block_root(
+        #[case] block_number: u64,
+        #[case] slot: u64,
+    ) {
+        let test_assets_dir =
+            format!("tests/mainnet/history/headers_with_proof/beacon_data/{block_number}");
+        let beacon_state_raw =
+            read_bytes_from_tests_submodule(format!("{test_assets_dir}/beacon_state.ssz",))
+                .unwrap();
+        let beacon_state =
+            BeaconState::from_ssz_bytes(&beacon_state_raw, ForkName::Capella).unwrap();
+        let beacon_state = beacon_state.as_capella().unwrap();
+        let block_raw =
+            read_bytes_from_tests_submodule(format!("{test_assets_dir}/block.ssz",)).unwrap();
+        let block = BeaconBlockCapella::from_ssz_bytes(&block_raw).unwrap();
+
+        let beacon_block_root = block.tree_hash_root();
+        let precomputed_proof = build_historical_summaries_proof_with_beacon_block_root(
+            slot,
+            beacon_state,
+            &block,
+            beacon_block_root,
+        );
+        let recomputed_proof = build_historical_summaries_proof(slot, beacon_state, block);
+
+        assert_eq!(precomputed_proof, recomputed_proof);
+    }
+
+    #[test]
+    fn dedup_proofs_in_hash_set() {
+        let historical_roots_proof = BlockProofHistoricalRoots {
+            beacon_block_proof: vec![B256::random(); 14].into(),
+            beacon_block_root: B256::random(),
+            execution_block_proof: vec![B256::random(); 11].into(),
+            slot: 4702208,
+        };
+        let historical_summaries_proof = BlockProofHistoricalSummaries {
+            beacon_block_proof: vec![B256::random(); 13].into(),
+            beacon_block_root: B256::random(),
+            execution_block_proof: vec![B256::random(); 11].into(),
+            slot: 6209538,
+        };
+        let mut other_historical_summaries_proof = historical_summaries_proof.clone();
+        other_historical_summaries_proof.slot += 1;
+
+        let proofs = HashSet::from([
+            historical_roots_proof.clone(),
+            historical_roots_proof.clone(),
+        ]);
+        assert_eq!(proofs.len(), 1);
+
+        let proofs = HashSet::from([
+            historical_summaries_proof.clone(),
+            historical_summaries_proof.clone(),
+            other_historical_summaries_proof.clone(),
+        ]);
+        assert_eq!(proofs.len(), 2);
+
+        let proofs = HashSet::from([
+            BlockHeaderProof::HistoricalHashes(vec![B256::random(); 15].into()),
+            BlockHeaderProof::HistoricalRoots(historical_roots_proof.clone()),
+            BlockHeaderProof::HistoricalRoots(historical_roots_proof),
+            BlockHeaderProof::HistoricalSummaries(historical_summaries_proof.clone()),
+            BlockHeaderProof::HistoricalSummaries(historical_summaries_proof),
+            BlockHeaderProof::HistoricalSummaries(other_historical_summaries_proof),
+        ]);
+        assert_eq!(proofs.len(), 4);
+    }
+
+    #[test]
+    fn from_stored_bytes_v1() {
+        use ssz::Encode;
+
+        let file =
+            std::fs::read_to_string("../../test_assets/mainnet/block_17034871_value.json").unwrap();
+        let json: Value = serde_json::from_str(&file).unwrap();
+        let header: Header = serde_json::from_value(json["result"].clone()).unwrap();
+        let proof = BlockProofHistoricalSummaries {
+            beacon_block_proof: vec![B256::random(); 13].into(),
+            beacon_block_root: B256::random(),
+            execution_block_proof: vec![B256::random(); 11].into(),
+            slot: 6209540,
+        };
+        let hwp = HeaderWithProof {
+            header: header.clone(),
+            proof: BlockHeaderProof::HistoricalSummaries(proof.clone()),
+        };
+
+        // v1 encodes the proof as a union, with the selector 3 for historical summaries proofs
+        let header_bytes = alloy::rlp::encode(&header);
+        let mut v1_bytes = vec![];
+        v1_bytes.extend_from_slice(&8_u32.to_le_bytes());
+        v1_bytes.extend_from_slice(&(8 + header_bytes.len() as u32).to_le_bytes());
+        v1_bytes.extend_from_slice(&header_bytes);
+        v1_bytes.push(3);
+        v1_bytes.extend_from_slice(&proof.as_ssz_bytes());
+
+        assert_eq!(
+            HeaderWithProof::from_stored_bytes(&v1_bytes, LEGACY_UNION_STORED_VERSION).unwrap(),
+            hwp
+        );
+        assert_eq!(
+            HeaderWithProof::from_stored_bytes(&hwp.as_ssz_bytes(), CURRENT_STORED_VERSION)
+                .unwrap(),
+            hwp
+        );
+
+        // The legacy `None` proof can't be upgraded
+        let none_bytes = [&v1_bytes[..8 + header_bytes.len()], &[0]].concat();
+        assert_eq!(
+            HeaderWithProof::from_stored_bytes(&none_bytes, LEGACY_UNION_STORED_VERSION),
+            Err(ssz::DecodeError::UnionSelectorInvalid(0))
+        );
+        assert!(HeaderWithProof::from_stored_bytes(&v1_bytes, 3).is_err());
+    }
+
+    #[rstest]
+    #[case::historical_hashes(HeaderWithProof {
+        header: Header::default(),
+        proof: BlockHeaderProof::HistoricalHashes(vec![B256::random(); 15].into()),
+    })]
+    #[case::historical_summaries(HeaderWithProof {
+        header: Header {
+            number: 17_034_871,
+            timestamp: SHANGHAI_TIMESTAMP + 12,
+            ..Default::default()
+        },
+        proof: BlockHeaderProof::HistoricalSummaries(BlockProofHistoricalSummaries {
+            beacon_block_proof: vec![B256::random(); 13].into(),
+            beacon_block_root: B256::random(),
+            execution_block_proof: vec![B256::random(); 12].into(),
+            slot: 6209538,
+        }),
+    })]
+    fn decode_preserving_proof_bytes_round_trip(#[case] header_with_proof: HeaderWithProof) {
+        let bytes = ssz::Encode::as_ssz_bytes(&header_with_proof);
+
+        let (decoded, proof_bytes) =
+            HeaderWithProof::decode_preserving_proof_bytes(&bytes).unwrap();
+        assert_eq!(decoded, header_with_proof);
+        assert_eq!(
+            proof_bytes.to_vec(),
+            ssz::Encode::as_ssz_bytes(&header_with_proof.proof)
+        );
+        assert_eq!(decoded.encode_with_proof_bytes(&proof_bytes), bytes);
+    }
+
+    #[test]
+    fn canonical_content_id_across_proof_variants() {
+        let header = Header {
+            number: 17_034_871,
+            ..Default::default()
+        };
+        let historical_roots_hwp = HeaderWithProof {
+            header: header.clone(),
+            proof: BlockHeaderProof::HistoricalRoots(BlockProofHistoricalRoots {
+                beacon_block_proof: vec![B256::random(); 14].into(),
+                beacon_block_root: B256::random(),
+                execution_block_proof: vec![B256::random(); 11].into(),
+                slot: 6209538,
+            }),
+        };
+        let historical_summaries_hwp = HeaderWithProof {
+            header: header.clone(),
+            proof: BlockHeaderProof::HistoricalSummaries(BlockProofHistoricalSummaries {
+                beacon_block_proof: vec![B256::random(); 13].into(),
+                beacon_block_root: B256::random(),
+                execution_block_proof: vec![B256::random(); 11].into(),
+                slot: 6209538,
+            }),
+        };
+
+        let content_id = historical_roots_hwp.canonical_content_id();
+        assert_eq!(content_id, historical_summaries_hwp.canonical_content_id());
+        assert_eq!(
+            content_id,
+            HistoryContentKey::new_block_header_by_hash(header.hash_slow()).content_id()
+        );
+
+        let other_hwp = HeaderWithProof {
+            header: Header {
+                number: 17_034_872,
+                ..header
+            },
+            ..historical_summaries_hwp
+        };
+        assert_ne!(content_id, other_hwp.canonical_content_id());
+    }
+
+    #[rstest]
+    #[case::first_offset_past_second_field(|_, second_offset, len| (second_offset + 4, len))]
+    #[case::first_offset_out_of_bounds(|_, _, len| (len + 1, len))]
+    #[case::decreasing_offsets(|first_offset, _, _| (first_offset, first_offset - 1))]
+    #[case::second_offset_out_of_bounds(|first_offset, _, len| (first_offset, len + 1))]
+    fn decode_with_corrupted_offsets(#[case] corrupt: fn(u32, u32, u32) -> (u32, u32)) {
+        let file =
+            std::fs::read_to_string("../../test_assets/mainnet/block_14764013_value.json").unwrap();
+        let json: Value = serde_json::from_str(&file).unwrap();
+        let header_with_proof = HeaderWithProof {
+            header: serde_json::from_value(json["result"].clone()).unwrap(),
+            proof: BlockHeaderProof::HistoricalHashes(vec![B256::random(); 15].into()),
+        };
+        let mut bytes = ssz::Encode::as_ssz_bytes(&header_with_proof);
+
+        let read_offset = |bytes: &[u8], index: usize| {
+            u32::from_le_bytes(bytes[index..index + 4].try_into().unwrap())
+        };
+        let (first_offset, second_offset) = corrupt(
+            read_offset(&bytes, 0),
+            read_offset(&bytes, 4),
+            bytes.len() as u32,
+        );
+        bytes[0..4].copy_from_slice(&first_offset.to_le_bytes());
+        bytes[4..8].copy_from_slice(&second_offset.to_le_bytes());
+
+        assert!(HeaderWithProof::from_ssz_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn estimate_proof_size_matches_encoded_proofs() {
+        let block = bellatrix_block();
+        let historical_batch = HistoricalBatch {
+            block_roots: vec![B256::random(); 8192].into(),
+            state_roots: vec![B256::random(); 8192].into(),
+        };
+        let historical_roots_proof =
+            build_historical_roots_proof(block.slot, &historical_batch, block);
+        assert_eq!(
+            estimate_proof_size(ForkName::Bellatrix),
+            ssz::Encode::ssz_bytes_len(&historical_roots_proof)
+        );
+
+        let capella_proof = build_block_proof_historical_summaries(
+            6_209_538,
+            vec![B256::random(); 8192].into(),
+            capella_block(6_209_538),
+        )
+        .unwrap();
+        assert_eq!(
+            estimate_proof_size(ForkName::Capella),
+            ssz::Encode::ssz_bytes_len(&capella_proof)
+        );
+
+        let block = deneb_block(8_626_178, B256::random());
+        let mut execution_block_proof = block.body.build_execution_block_hash_proof();
+        execution_block_proof.extend(block.build_body_root_proof());
+        let deneb_proof = BlockProofHistoricalSummaries {
+            beacon_block_proof: vec![B256::random(); 13].into(),
+            beacon_block_root: block.tree_hash_root(),
+            execution_block_proof: execution_block_proof.into(),
+            slot: block.slot,
+        };
+        assert_eq!(
+            estimate_proof_size(ForkName::Deneb),
+            ssz::Encode::ssz_bytes_len(&deneb_proof)
+        );
+    }
 }
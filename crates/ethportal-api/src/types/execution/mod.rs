@@ -1,6 +1,8 @@
 pub mod accumulator;
+pub mod beacon_block_proof_cache;
 pub mod block_body;
 pub mod ephermeral_header;
+pub mod header;
 pub mod header_with_proof;
 pub mod receipts;
 pub mod ssz_header;
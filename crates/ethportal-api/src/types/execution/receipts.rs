@@ -1,9 +1,10 @@
 use alloy::{
-    consensus::{proofs::calculate_receipt_root, ReceiptEnvelope},
+    consensus::{proofs::calculate_receipt_root, Header, ReceiptEnvelope},
     eips::{Decodable2718, Encodable2718},
-    primitives::B256,
+    primitives::{Bloom, B256},
 };
 use alloy_rlp::{RlpDecodableWrapper, RlpEncodableWrapper};
+use anyhow::bail;
 use serde::{Deserialize, Deserializer};
 use serde_json::Value;
 
@@ -18,6 +19,29 @@ impl Receipts {
     pub fn root(&self) -> B256 {
         calculate_receipt_root(&self.0)
     }
+
+    pub fn validate_against_header(&self, header: &Header) -> anyhow::Result<()> {
+        let receipts_root = self.root();
+        if receipts_root != header.receipts_root {
+            bail!(
+                "Receipts root doesn't match header receipts root: {receipts_root:?} - {:?}",
+                header.receipts_root
+            );
+        }
+        Ok(())
+    }
+
+    /// Returns the bloom filter of all the logs in the block, by ORing the bloom of every receipt.
+    pub fn aggregate_bloom(&self) -> Bloom {
+        self.0
+            .iter()
+            .fold(Bloom::ZERO, |bloom, receipt| bloom | *receipt.logs_bloom())
+    }
+
+    /// Returns whether the aggregated bloom of the receipts matches the header's `logs_bloom`.
+    pub fn verify_bloom(&self, header: &Header) -> bool {
+        self.aggregate_bloom() == header.logs_bloom
+    }
 }
 
 impl ssz::Encode for Receipts {
@@ -394,6 +418,26 @@ mod tests {
         assert_eq!(receipts.root(), expected_receipts_root);
     }
 
+    #[test_log::test]
+    fn aggregate_bloom_matches_header() {
+        let block =
+            std::fs::read_to_string("../../test_assets/mainnet/block_19433902_value.json").unwrap();
+        let block: Value = serde_json::from_str(&block).unwrap();
+        let header: Header = serde_json::from_value(block["result"].clone()).unwrap();
+        let receipts =
+            std::fs::read_to_string("../../test_assets/infura_batch/receipts-19433902.json")
+                .unwrap();
+        let mut receipts: Receipts = serde_json::from_str(&receipts).unwrap();
+
+        assert_eq!(receipts.aggregate_bloom(), header.logs_bloom);
+        assert!(receipts.verify_bloom(&header));
+
+        // Remove the receipts with logs
+        receipts.0.retain(|receipt| receipt.logs().is_empty());
+        assert_eq!(receipts.aggregate_bloom(), Bloom::ZERO);
+        assert!(!receipts.verify_bloom(&header));
+    }
+
     const EXPECTED_RECEIPTS_ROOT: &str =
         "0x168a3827607627e781941dc777737fc4b6beb69a8b139240b881992b35b854ea";
     const RECEIPT_0: &str = "0x02f90554018302e56fb9010000200000000000001000000080000000000000000000010000000000000000000000010000000000000090000001010002000000080008000000000000000000000000000000000000020008000000200000000000400000000004000000400000000000000000000000000000000000000000000000040000000010000000000000010000001100000000000000008000000000000000080020004000100000000000000000000000000080000000000000000000000000000000000000000001000002000000100004000000000000000000000000001000000002000000000024200000000000000000000000000000000000004000000000000000001000f90449f89b94dac17f958d2ee523a2206206994597c13d831ec7f863a0ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3efa0000000000000000000000000dd19b32a084be0a318f11edb3f7034889c03c51fa000000000000000000000000074de5d4fcbf63e00296fd95d33236b9794016631a000000000000000000000000000000000000000000000000000000000979aedebf89b94dac17f958d2ee523a2206206994597c13d831ec7f863a0ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3efa000000000000000000000000074de5d4fcbf63e00296fd95d33236b9794016631a000000000000000000000000074c99f3f5331676f6aec2756e1f39b4fc029a83ea000000000000000000000000000000000000000000000000000000000979aedebf89b94c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2f863a0ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3efa000000000000000000000000074c99f3f5331676f6aec2756e1f39b4fc029a83ea00000000000000000000000001111111254fb6c44bac0bed2854e76f90643097da000000000000000000000000000000000000000000000000011f8b9803bc57124f8799474c99f3f5331676f6aec2756e1f39b4fc029a83ee1a01c411e9a96e071241c2f21f7726b17ae89e3cab4c78be50e062b03a9fffbbad1b8400000000000000000000000000000000000000000000000657acd23da825d7df70000000000000000000000000000000000000000000000000000035616e4172af8fc9474c99f3f5331676f6aec2756e1f39b4fc029a83ef863a0d78ad95fa46c994b6551d0da85fc275fe613ce37657fb8d5e3d130840159d822a00000000000000000000000001111111254fb6c44bac0bed2854e76f90643097da00000000000000000000000001111111254fb6c44bac0bed2854e76f90643097db880000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000979aedeb00000000000000000000000000000000000000000000000011f8b9803bc571240000000000000000000000000000000000000000000000000000000000000000f87a94c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2f842a07fcf532c15f0a6db0bd6d0e038bea71d30d808c7d98cb3bf7268a95bf5081b65a00000000000000000000000001111111254fb6c44bac0bed2854e76f90643097da000000000000000000000000000000000000000000000000011f8b9803bc57124f87b94881d40237659c251811cec9c364ef91dc08d300cf863a0beee1e6e7fe307ddcf84b0a16137a4430ad5e2480fc4f4a8e250ab56ccd7630da0bd5c436f8c83379009c1962310b8347e561d1900906d3fe4075b1596f8955f88a0000000000000000000000000dd19b32a084be0a318f11edb3f7034889c03c51f80";
@@ -3,6 +3,8 @@ use std::io::{self, BufRead, Read, Write};
 use alloy::primitives::Bytes;
 use anyhow::anyhow;
 use bytes::{buf::Reader, Buf, BufMut, BytesMut};
+use ethportal_api::types::execution::header_with_proof::HeaderWithProof;
+use ssz::{Decode, Encode};
 
 fn decode_next_content_item(reader: &mut Reader<Bytes>) -> io::Result<Option<Bytes>> {
     if reader.fill_buf()?.is_empty() {
@@ -72,9 +74,46 @@ pub fn encode_content_payload(content_items: &[Bytes]) -> anyhow::Result<BytesMu
     Ok(content_payload.into_inner())
 }
 
+/// Writes the headers with proof to the writer, each one SSZ encoded and prefixed with a LEB128
+/// varint of its length, the same framing as [encode_content_payload].
+///
+/// Items are encoded and written one at a time, so the whole batch is never buffered at once.
+pub fn encode_header_batch_framed<W: Write>(
+    items: &[HeaderWithProof],
+    writer: &mut W,
+) -> io::Result<()> {
+    for item in items {
+        let encoded_item = item.as_ssz_bytes();
+        let length = u32::try_from(encoded_item.len()).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Header batch item exceeds max allowed size of u32 bytes",
+            )
+        })?;
+        leb128::write::unsigned(writer, length as u64)?;
+        writer.write_all(&encoded_item)?;
+    }
+    Ok(())
+}
+
+/// Reads the headers with proof written by [encode_header_batch_framed], until the reader is
+/// exhausted.
+pub fn decode_header_batch_framed<R: BufRead>(reader: &mut R) -> io::Result<Vec<HeaderWithProof>> {
+    let mut items = vec![];
+    while !reader.fill_buf()?.is_empty() {
+        let length = read_varint(reader)?;
+        let mut encoded_item = vec![0; length as usize];
+        reader.read_exact(&mut encoded_item)?;
+        let item = HeaderWithProof::from_ssz_bytes(&encoded_item)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, format!("{err:?}")))?;
+        items.push(item);
+    }
+    Ok(items)
+}
+
 /// Try to read up to five LEB128 bytes (The maximum content size allowed for this application is
 /// limited to `uint32`).
-pub fn read_varint(reader: &mut Reader<Bytes>) -> io::Result<u32> {
+pub fn read_varint<R: Read>(reader: &mut R) -> io::Result<u32> {
     let varint = leb128::read::unsigned(reader)
         .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
     u32::try_from(varint).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))
@@ -83,7 +122,11 @@ pub fn read_varint(reader: &mut Reader<Bytes>) -> io::Result<u32> {
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod test {
-    use ethportal_api::utils::bytes::hex_decode;
+    use alloy::{consensus::Header, primitives::B256};
+    use ethportal_api::{
+        types::execution::header_with_proof::{BlockHeaderProof, BlockProofHistoricalSummaries},
+        utils::bytes::hex_decode,
+    };
     use rstest::rstest;
 
     use super::*;
@@ -169,4 +212,70 @@ mod test {
 
         assert_eq!(content_items, expected_content_items);
     }
+
+    fn header(number: u64, timestamp: u64) -> Header {
+        Header {
+            number,
+            timestamp,
+            parent_hash: B256::random(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_encode_decode_header_batch_framed() {
+        let historical_summaries_proof = || {
+            BlockHeaderProof::HistoricalSummaries(BlockProofHistoricalSummaries {
+                beacon_block_proof: vec![B256::random(); 13].into(),
+                beacon_block_root: B256::random(),
+                execution_block_proof: vec![B256::random(); 11].into(),
+                slot: 8_577_095,
+            })
+        };
+        let items = vec![
+            HeaderWithProof {
+                header: header(14_764_013, 1_652_700_000),
+                proof: BlockHeaderProof::HistoricalHashes(vec![B256::random(); 15].into()),
+            },
+            HeaderWithProof {
+                header: header(19_433_902, 1_710_430_000),
+                proof: historical_summaries_proof(),
+            },
+            HeaderWithProof {
+                header: header(19_433_903, 1_710_430_012),
+                proof: historical_summaries_proof(),
+            },
+        ];
+
+        let mut buf = vec![];
+        encode_header_batch_framed(&items, &mut buf).unwrap();
+        assert_eq!(
+            decode_header_batch_framed(&mut buf.as_slice()).unwrap(),
+            items
+        );
+
+        // The framing is the same as the one of the content payload
+        let content_items: Vec<Bytes> = items
+            .iter()
+            .map(|item| item.as_ssz_bytes().into())
+            .collect();
+        assert_eq!(
+            encode_content_payload(&content_items).unwrap().to_vec(),
+            buf
+        );
+    }
+
+    #[test]
+    fn test_decode_header_batch_framed_truncated() {
+        let item = HeaderWithProof {
+            header: header(14_764_013, 1_652_700_000),
+            proof: BlockHeaderProof::HistoricalHashes(vec![B256::random(); 15].into()),
+        };
+        let mut buf = vec![];
+        encode_header_batch_framed(&[item], &mut buf).unwrap();
+        buf.pop();
+
+        let err = decode_header_batch_framed(&mut buf.as_slice()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
 }
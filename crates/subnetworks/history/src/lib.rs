@@ -5,7 +5,9 @@ pub mod events;
 mod jsonrpc;
 pub mod network;
 mod ping_extensions;
+pub mod requests;
 mod storage;
+pub mod trie_key;
 pub mod validation;
 
 use std::sync::Arc;
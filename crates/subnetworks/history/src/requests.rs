@@ -0,0 +1,158 @@
+//! Execution layer requests of a block (EIP-7685), such as the withdrawal requests (EIP-7002) and
+//! consolidation requests (EIP-7251), which the header commits to with its `requests_hash`.
+//!
+//! The requests are in their flat encoding, as in the `executionRequests` of the Engine API: every
+//! request is its `request_type` byte followed by its `request_data`.
+
+use alloy::{
+    consensus::Header,
+    primitives::{Bytes, B256},
+};
+use thiserror::Error;
+
+/// An error decoding the flat encoding of the requests.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum RequestsError {
+    /// The request doesn't have any `request_data`, so it should have been omitted.
+    #[error("request {index} has no request data")]
+    EmptyRequest { index: usize },
+    /// The request types are not in strictly ascending order.
+    #[error("request {index} has type {request_type}, which isn't after the previous type")]
+    UnorderedRequestType { index: usize, request_type: u8 },
+}
+
+/// The execution layer requests of a block, ordered by `request_type`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Requests(Vec<Bytes>);
+
+impl Requests {
+    /// Decodes the requests from their flat encoding, checking that every request has
+    /// `request_data` and that the request types are strictly ascending.
+    pub fn decode(requests: Vec<Bytes>) -> Result<Self, RequestsError> {
+        let mut previous_type = None;
+        for (index, request) in requests.iter().enumerate() {
+            let Some((request_type, request_data)) = request.split_first() else {
+                return Err(RequestsError::EmptyRequest { index });
+            };
+            if request_data.is_empty() {
+                return Err(RequestsError::EmptyRequest { index });
+            }
+            if previous_type.is_some_and(|previous_type| previous_type >= *request_type) {
+                return Err(RequestsError::UnorderedRequestType {
+                    index,
+                    request_type: *request_type,
+                });
+            }
+            previous_type = Some(*request_type);
+        }
+        Ok(Self(requests))
+    }
+
+    /// Returns the requests hash, as defined in EIP-7685:
+    /// `sha256(sha256(requests_0) ++ sha256(requests_1) ++ ...)`.
+    ///
+    /// Without any requests, this is `sha256("")`.
+    pub fn hash(&self) -> B256 {
+        let request_hashes: Vec<u8> = self
+            .0
+            .iter()
+            .flat_map(|request| ethereum_hashing::hash(request))
+            .collect();
+        B256::from_slice(&ethereum_hashing::hash(&request_hashes))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Returns whether the requests match the header's `requests_hash`.
+///
+/// Pre-Prague headers don't have a `requests_hash`, so no requests match them.
+pub fn verify_requests(header: &Header, requests: &Requests) -> bool {
+    header.requests_hash == Some(requests.hash())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use alloy::{eips::eip7685::EMPTY_REQUESTS_HASH, primitives::b256};
+
+    use super::*;
+
+    fn prague_header(requests_hash: B256) -> Header {
+        Header {
+            number: 22_431_084,
+            requests_hash: Some(requests_hash),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn empty_requests() {
+        let requests = Requests::decode(vec![]).unwrap();
+        assert!(requests.is_empty());
+        assert_eq!(requests.hash(), EMPTY_REQUESTS_HASH);
+        assert_eq!(
+            EMPTY_REQUESTS_HASH,
+            b256!("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855")
+        );
+
+        // Most Prague blocks don't have any requests
+        assert!(verify_requests(
+            &prague_header(EMPTY_REQUESTS_HASH),
+            &requests
+        ));
+        assert!(!verify_requests(&Header::default(), &requests));
+    }
+
+    #[test]
+    fn requests_hash() {
+        // A withdrawal request (type 0x01) and a consolidation request (type 0x02)
+        let withdrawal_request = Bytes::from([vec![0x01], vec![0xaa; 76]].concat());
+        let consolidation_request = Bytes::from([vec![0x02], vec![0xbb; 116]].concat());
+        let requests = Requests::decode(vec![
+            withdrawal_request.clone(),
+            consolidation_request.clone(),
+        ])
+        .unwrap();
+        assert_eq!(requests.len(), 2);
+
+        let mut request_hashes = ethereum_hashing::hash(&withdrawal_request);
+        request_hashes.extend(ethereum_hashing::hash(&consolidation_request));
+        let requests_hash = B256::from_slice(&ethereum_hashing::hash(&request_hashes));
+        assert_eq!(requests.hash(), requests_hash);
+
+        assert!(verify_requests(&prague_header(requests_hash), &requests));
+        assert!(!verify_requests(
+            &prague_header(EMPTY_REQUESTS_HASH),
+            &requests
+        ));
+    }
+
+    #[test]
+    fn decode_invalid_requests() {
+        assert_eq!(
+            Requests::decode(vec![Bytes::from(vec![0x01, 0xaa]), Bytes::from(vec![0x02])]),
+            Err(RequestsError::EmptyRequest { index: 1 })
+        );
+        assert_eq!(
+            Requests::decode(vec![Bytes::new()]),
+            Err(RequestsError::EmptyRequest { index: 0 })
+        );
+        assert_eq!(
+            Requests::decode(vec![
+                Bytes::from(vec![0x02, 0xaa]),
+                Bytes::from(vec![0x01, 0xbb])
+            ]),
+            Err(RequestsError::UnorderedRequestType {
+                index: 1,
+                request_type: 1
+            })
+        );
+    }
+}
@@ -0,0 +1,33 @@
+//! Keys of the receipts and transactions tries of a block.
+//!
+//! Both tries are keyed by the RLP encoding of the index of the item in the block (the same
+//! encoding as geth's `rlp.AppendUint64`), so these keys can be used to build or verify a proof
+//! of a single receipt or transaction against the header's `receipts_root` or
+//! `transactions_root`.
+
+/// Returns the key of the receipt with the given index in the receipts trie.
+pub fn receipt_trie_key(index: u64) -> Vec<u8> {
+    alloy::rlp::encode(index)
+}
+
+/// Returns the key of the transaction with the given index in the transactions trie.
+pub fn transaction_trie_key(index: u64) -> Vec<u8> {
+    alloy::rlp::encode(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::zero(0, vec![0x80])]
+    #[case::one(1, vec![0x01])]
+    #[case::single_byte(127, vec![0x7f])]
+    #[case::multi_byte(256, vec![0x82, 0x01, 0x00])]
+    fn trie_key(#[case] index: u64, #[case] expected: Vec<u8>) {
+        assert_eq!(receipt_trie_key(index), expected);
+        assert_eq!(transaction_trie_key(index), expected);
+    }
+}
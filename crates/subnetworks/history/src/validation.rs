@@ -1,15 +1,15 @@
 use std::sync::Arc;
 
 use alloy::{consensus::Header, primitives::B256};
-use anyhow::{anyhow, ensure};
+use anyhow::anyhow;
 use ethportal_api::{
     types::execution::{
         block_body::BlockBody, header_with_proof::HeaderWithProof, receipts::Receipts,
     },
-    utils::bytes::hex_encode,
     HistoryContentKey,
 };
 use ssz::Decode;
+use thiserror::Error;
 use tokio::sync::RwLock;
 use trin_validation::{
     oracle::HeaderOracle,
@@ -20,6 +20,95 @@ pub struct ChainHistoryValidator {
     pub header_oracle: Arc<RwLock<HeaderOracle>>,
 }
 
+impl ChainHistoryValidator {
+    async fn validate_header(
+        &self,
+        content_key: &HistoryContentKey,
+        content: &[u8],
+    ) -> anyhow::Result<()> {
+        let header_with_proof = HeaderWithProof::from_ssz_bytes(content)
+            .map_err(|err| anyhow!("Header content has invalid encoding: {err:?}"))?;
+        validate_key_value_pair(content_key, &header_with_proof)
+            .map_err(|err| anyhow!("Content validation failed: {err}"))?;
+        self.header_oracle
+            .read()
+            .await
+            .header_validator
+            .validate_header_with_proof(&header_with_proof)
+    }
+
+    /// Finds the header for the given block hash and verifies its proof.
+    async fn find_trusted_header(&self, block_hash: B256) -> anyhow::Result<Header> {
+        Ok(self
+            .header_oracle
+            .read()
+            .await
+            .recursive_find_header_by_hash_with_proof(block_hash)
+            .await?
+            .header)
+    }
+}
+
+/// An error validating that the content value matches the block identified by the content key.
+#[derive(Debug, Error)]
+pub enum HistoryValidationError {
+    #[error("Invalid header hash. Found: {found} - Expected: {expected}")]
+    InvalidHeaderHash { found: B256, expected: B256 },
+    #[error("Invalid header number. Found: {found} - Expected: {expected}")]
+    InvalidHeaderNumber { found: u64, expected: u64 },
+    #[error("Content key is not a block header key: {0}")]
+    UnexpectedContentKey(HistoryContentKey),
+}
+
+/// Validates that the header with proof is the block identified by the content key.
+///
+/// This only checks the identity of the header (its hash or number), not its proof.
+pub fn validate_key_value_pair(
+    content_key: &HistoryContentKey,
+    header_with_proof: &HeaderWithProof,
+) -> Result<(), HistoryValidationError> {
+    match content_key {
+        HistoryContentKey::BlockHeaderByHash(key) => {
+            let expected = B256::from(key.block_hash);
+            let found = header_with_proof.header.hash_slow();
+            if found != expected {
+                return Err(HistoryValidationError::InvalidHeaderHash { found, expected });
+            }
+        }
+        HistoryContentKey::BlockHeaderByNumber(key) => {
+            let expected = key.block_number;
+            let found = header_with_proof.header.number;
+            if found != expected {
+                return Err(HistoryValidationError::InvalidHeaderNumber { found, expected });
+            }
+        }
+        _ => {
+            return Err(HistoryValidationError::UnexpectedContentKey(
+                content_key.clone(),
+            ))
+        }
+    }
+    Ok(())
+}
+
+/// Validates the block body against the roots committed to in the trusted header.
+fn validate_block_body(content: &[u8], trusted_header: &Header) -> anyhow::Result<()> {
+    let block_body = BlockBody::from_ssz_bytes(content)
+        .map_err(|err| anyhow!("Block Body content has invalid encoding: {err:?}"))?;
+    block_body
+        .validate_against_header(trusted_header)
+        .map_err(|err| anyhow!("Content validation failed: {err}"))
+}
+
+/// Validates the receipts against the receipts root of the trusted header.
+fn validate_block_receipts(content: &[u8], trusted_header: &Header) -> anyhow::Result<()> {
+    let receipts = Receipts::from_ssz_bytes(content)
+        .map_err(|err| anyhow!("Block Receipts content has invalid encoding: {err:?}"))?;
+    receipts
+        .validate_against_header(trusted_header)
+        .map_err(|err| anyhow!("Content validation failed: {err}"))
+}
+
 impl Validator<HistoryContentKey> for ChainHistoryValidator {
     async fn validate_content(
         &self,
@@ -27,110 +116,44 @@ impl Validator<HistoryContentKey> for ChainHistoryValidator {
         content: &[u8],
     ) -> anyhow::Result<ValidationResult<HistoryContentKey>> {
         match content_key {
-            HistoryContentKey::BlockHeaderByHash(key) => {
-                let header_with_proof =
-                    HeaderWithProof::from_ssz_bytes(content).map_err(|err| {
-                        anyhow!("Header by hash content has invalid encoding: {err:?}")
-                    })?;
-                let header_hash = header_with_proof.header.hash_slow();
-                ensure!(
-                    header_hash == B256::from(key.block_hash),
-                    "Content validation failed: Invalid header hash. Found: {header_hash:?} - Expected: {:?}",
-                    hex_encode(header_hash)
-                );
-                self.header_oracle
-                    .read()
-                    .await
-                    .header_validator
-                    .validate_header_with_proof(&header_with_proof)?;
-
-                Ok(ValidationResult::new(true))
-            }
-            HistoryContentKey::BlockHeaderByNumber(key) => {
-                let header_with_proof =
-                    HeaderWithProof::from_ssz_bytes(content).map_err(|err| {
-                        anyhow!("Header by number content has invalid encoding: {err:?}")
-                    })?;
-                let header_number = header_with_proof.header.number;
-                ensure!(
-                    header_number == key.block_number,
-                    "Content validation failed: Invalid header number. Found: {header_number} - Expected: {}",
-                    key.block_number
-                );
-                self.header_oracle
-                    .read()
-                    .await
-                    .header_validator
-                    .validate_header_with_proof(&header_with_proof)?;
-
-                Ok(ValidationResult::new(true))
+            HistoryContentKey::BlockHeaderByHash(_) | HistoryContentKey::BlockHeaderByNumber(_) => {
+                self.validate_header(content_key, content).await?
             }
             HistoryContentKey::BlockBody(key) => {
-                let block_body = BlockBody::from_ssz_bytes(content)
-                    .map_err(|msg| anyhow!("Block Body content has invalid encoding: {:?}", msg))?;
-                let trusted_header: Header = self
-                    .header_oracle
-                    .read()
-                    .await
-                    .recursive_find_header_by_hash_with_proof(B256::from(key.block_hash))
-                    .await?
-                    .header;
-                let actual_uncles_root = block_body.calculate_ommers_root();
-                if actual_uncles_root != trusted_header.ommers_hash {
-                    return Err(anyhow!(
-                        "Content validation failed: Invalid uncles root. Found: {:?} - Expected: {:?}",
-                        actual_uncles_root,
-                        trusted_header.ommers_hash
-                    ));
-                }
-                let actual_txs_root = block_body.transactions_root();
-                if actual_txs_root != trusted_header.transactions_root {
-                    return Err(anyhow!(
-                        "Content validation failed: Invalid transactions root. Found: {:?} - Expected: {:?}",
-                        actual_txs_root,
-                        trusted_header.transactions_root
-                    ));
-                }
-                Ok(ValidationResult::new(true))
+                let trusted_header = self.find_trusted_header(B256::from(key.block_hash)).await?;
+                validate_block_body(content, &trusted_header)?
             }
             HistoryContentKey::BlockReceipts(key) => {
-                let receipts = Receipts::from_ssz_bytes(content).map_err(|msg| {
-                    anyhow!("Block Receipts content has invalid encoding: {:?}", msg)
-                })?;
-                let trusted_header: Header = self
-                    .header_oracle
-                    .read()
-                    .await
-                    .recursive_find_header_by_hash_with_proof(B256::from(key.block_hash))
-                    .await?
-                    .header;
-                let actual_receipts_root = receipts.root();
-                if actual_receipts_root != trusted_header.receipts_root {
-                    return Err(anyhow!(
-                        "Content validation failed: Invalid receipts root. Found: {:?} - Expected: {:?}",
-                        actual_receipts_root,
-                        trusted_header.receipts_root
-                    ));
-                }
-                Ok(ValidationResult::new(true))
+                let trusted_header = self.find_trusted_header(B256::from(key.block_hash)).await?;
+                validate_block_receipts(content, &trusted_header)?
+            }
+            HistoryContentKey::EphemeralHeaderOffer(_) => {
+                return Err(anyhow!(
+                    "Validation is not implemented for EphemeralHeaderOffer yet"
+                ))
+            }
+            HistoryContentKey::EphemeralHeadersFindContent(_) => {
+                return Err(anyhow!(
+                    "Validation is not implemented for EphemeralHeadersFindContent yet"
+                ))
             }
-            HistoryContentKey::EphemeralHeaderOffer(_) => Err(anyhow!(
-                "Validation is not implemented for EphemeralHeaderOffer yet"
-            )),
-            HistoryContentKey::EphemeralHeadersFindContent(_) => Err(anyhow!(
-                "Validation is not implemented for EphemeralHeadersFindContent yet"
-            )),
         }
+        Ok(ValidationResult::new(true))
     }
 }
 
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod tests {
-    use ethportal_api::utils::bytes::hex_decode;
+    use ethportal_api::{
+        types::execution::header_with_proof::BlockHeaderProof, utils::bytes::hex_decode,
+    };
     use serde_json::Value;
     use ssz::Encode;
-    use trin_utils::submodules::read_portal_spec_tests_file;
+    use trin_utils::{
+        submodules::read_portal_spec_tests_file,
+        test_assets::{read_test_assets_file, read_test_assets_file_as_bytes},
+    };
 
     use super::*;
 
@@ -146,6 +169,12 @@ mod tests {
         hex_decode(raw_header).unwrap()
     }
 
+    fn get_14764013_header() -> Header {
+        let file = read_test_assets_file("mainnet/block_14764013_value.json").unwrap();
+        let json: Value = serde_json::from_str(&file).unwrap();
+        serde_json::from_value(json["result"].clone()).unwrap()
+    }
+
     #[test_log::test(tokio::test)]
     async fn validate_header_by_hash() {
         let header_with_proof_ssz = get_header_with_proof_ssz();
@@ -258,6 +287,99 @@ mod tests {
             .unwrap();
     }
 
+    #[test_log::test]
+    fn validate_block_body_against_header() {
+        let header = get_14764013_header();
+        let content = read_test_assets_file_as_bytes("mainnet/block_body_14764013.bin").unwrap();
+        validate_block_body(&content, &header).unwrap();
+    }
+
+    #[test_log::test]
+    #[should_panic(expected = "Block body txs root doesn't match header txs root")]
+    fn invalidate_block_body_with_invalid_transactions_root() {
+        let mut header = get_14764013_header();
+        header.transactions_root = B256::random();
+        let content = read_test_assets_file_as_bytes("mainnet/block_body_14764013.bin").unwrap();
+        validate_block_body(&content, &header).unwrap();
+    }
+
+    #[test_log::test]
+    #[should_panic(expected = "Block Body content has invalid encoding")]
+    fn invalidate_block_body_with_invalid_encoding() {
+        let header = get_14764013_header();
+        validate_block_body(&[0x01, 0x02, 0x03], &header).unwrap();
+    }
+
+    #[test_log::test]
+    fn validate_block_receipts_against_header() {
+        let header = get_14764013_header();
+        let content = read_test_assets_file_as_bytes("mainnet/receipts_14764013.bin").unwrap();
+        validate_block_receipts(&content, &header).unwrap();
+    }
+
+    #[test_log::test]
+    #[should_panic(expected = "Receipts root doesn't match header receipts root")]
+    fn invalidate_block_receipts_with_invalid_receipts_root() {
+        let mut header = get_14764013_header();
+        header.receipts_root = B256::random();
+        let content = read_test_assets_file_as_bytes("mainnet/receipts_14764013.bin").unwrap();
+        validate_block_receipts(&content, &header).unwrap();
+    }
+
+    #[test_log::test]
+    #[should_panic(expected = "Block Receipts content has invalid encoding")]
+    fn invalidate_block_receipts_with_invalid_encoding() {
+        let header = get_14764013_header();
+        validate_block_receipts(&[0x01, 0x02, 0x03], &header).unwrap();
+    }
+
+    fn get_14764013_header_with_proof() -> HeaderWithProof {
+        HeaderWithProof {
+            header: get_14764013_header(),
+            proof: BlockHeaderProof::HistoricalHashes(Default::default()),
+        }
+    }
+
+    #[test_log::test]
+    fn validate_key_value_pair_by_hash() {
+        let header_with_proof = get_14764013_header_with_proof();
+        let content_key =
+            HistoryContentKey::new_block_header_by_hash(header_with_proof.header.hash_slow());
+        validate_key_value_pair(&content_key, &header_with_proof).unwrap();
+
+        let content_key = HistoryContentKey::new_block_header_by_hash(B256::random());
+        assert!(matches!(
+            validate_key_value_pair(&content_key, &header_with_proof),
+            Err(HistoryValidationError::InvalidHeaderHash { .. })
+        ));
+    }
+
+    #[test_log::test]
+    fn validate_key_value_pair_by_number() {
+        let header_with_proof = get_14764013_header_with_proof();
+        let content_key = HistoryContentKey::new_block_header_by_number(14_764_013);
+        validate_key_value_pair(&content_key, &header_with_proof).unwrap();
+
+        let content_key = HistoryContentKey::new_block_header_by_number(14_764_014);
+        assert!(matches!(
+            validate_key_value_pair(&content_key, &header_with_proof),
+            Err(HistoryValidationError::InvalidHeaderNumber {
+                found: 14_764_013,
+                expected: 14_764_014,
+            })
+        ));
+    }
+
+    #[test_log::test]
+    fn validate_key_value_pair_with_block_body_key() {
+        let header_with_proof = get_14764013_header_with_proof();
+        let content_key = HistoryContentKey::new_block_body(header_with_proof.header.hash_slow());
+        assert!(matches!(
+            validate_key_value_pair(&content_key, &header_with_proof),
+            Err(HistoryValidationError::UnexpectedContentKey(_))
+        ));
+    }
+
     fn default_header_oracle() -> Arc<RwLock<HeaderOracle>> {
         Arc::new(RwLock::new(HeaderOracle::default()))
     }
@@ -5,3 +5,5 @@ pub mod cli;
 pub mod dir;
 pub mod log;
 pub mod submodules;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_assets;
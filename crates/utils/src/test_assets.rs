@@ -0,0 +1,29 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+pub const TEST_ASSETS_PATH: [&str; 2] = ["../../test_assets", "../../../test_assets"];
+
+/// Returns a path to a file within the "test_assets" directory
+pub fn test_assets_file_path<P: AsRef<Path>>(path: P) -> PathBuf {
+    for test_assets_path in TEST_ASSETS_PATH {
+        if fs::exists(test_assets_path)
+            .expect("we should be able to check whether test assets path exists")
+        {
+            return PathBuf::from(test_assets_path).join(path);
+        }
+    }
+
+    panic!("Test assets directory not found!")
+}
+
+/// Reads text file from the "test_assets" directory
+pub fn read_test_assets_file<P: AsRef<Path>>(path: P) -> io::Result<String> {
+    fs::read_to_string(test_assets_file_path(path))
+}
+
+/// Reads binary file from the "test_assets" directory
+pub fn read_test_assets_file_as_bytes<P: AsRef<Path>>(path: P) -> io::Result<Vec<u8>> {
+    fs::read(test_assets_file_path(path))
+}
@@ -0,0 +1,63 @@
+use std::{fs, hint::black_box};
+
+use alloy::{
+    consensus::Header,
+    primitives::hex,
+    rlp::{Decodable, Encodable},
+};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use serde_json::Value;
+use trin_utils::test_assets::read_test_assets_file;
+
+/// Reads the RLP encoded header from `src/assets/header_rlps.json`.
+fn read_header_rlp(block_number: u64) -> Vec<u8> {
+    let file = fs::read_to_string("./src/assets/header_rlps.json").unwrap();
+    let json: Value = serde_json::from_str(&file).unwrap();
+    let raw_header = json[block_number.to_string()].as_str().unwrap();
+    hex::decode(raw_header).unwrap()
+}
+
+/// Reads the header from `eth_getBlockByNumber` response stored in the "test_assets" directory.
+fn read_block_header(block_number: u64) -> Header {
+    let file = read_test_assets_file(format!("mainnet/block_{block_number}_value.json")).unwrap();
+    let json: Value = serde_json::from_str(&file).unwrap();
+    serde_json::from_value(json["result"].clone()).unwrap()
+}
+
+/// RLP encoded headers, one for each fork that changed the header fields.
+fn header_rlps() -> Vec<(&'static str, Vec<u8>)> {
+    let encode = |header: Header| alloy::rlp::encode(header);
+    vec![
+        ("pre-merge", read_header_rlp(1_000_001)),
+        ("london", encode(read_block_header(14_764_013))),
+        ("shanghai", encode(read_block_header(17_034_871))),
+        ("cancun", encode(read_block_header(19_433_902))),
+    ]
+}
+
+fn bench_header(c: &mut Criterion) {
+    let mut group = c.benchmark_group("header");
+
+    for (fork, rlp) in header_rlps() {
+        let header = Header::decode(&mut rlp.as_slice()).unwrap();
+
+        group.bench_with_input(BenchmarkId::new("decode_rlp", fork), &rlp, |b, rlp| {
+            b.iter(|| Header::decode(&mut black_box(rlp.as_slice())).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("hash", fork), &header, |b, header| {
+            b.iter(|| black_box(header).hash_slow())
+        });
+        group.bench_with_input(BenchmarkId::new("encode", fork), &header, |b, header| {
+            b.iter(|| {
+                let mut buf = Vec::with_capacity(header.length());
+                black_box(header).encode(&mut buf);
+                buf
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_header);
+criterion_main!(benches);
@@ -0,0 +1,164 @@
+use std::{fs, hint::black_box};
+
+use alloy::{
+    consensus::{proofs::calculate_transaction_root, Header, TxEnvelope, EMPTY_OMMER_ROOT_HASH},
+    primitives::{hex, Bloom, B256, B64, U256},
+    rlp::Decodable,
+};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use ethportal_api::{
+    consensus::historical_summaries::{HistoricalSummaries, HistoricalSummary},
+    types::{
+        consensus::{
+            beacon_block::SignedBeaconBlock, beacon_state::HistoricalBatch, fork::ForkName,
+        },
+        execution::header_with_proof::{
+            build_block_proof_historical_roots, BlockHeaderProof, BlockProofHistoricalSummaries,
+            HeaderWithProof,
+        },
+    },
+};
+use serde_json::Value;
+use tree_hash::TreeHash;
+use trin_utils::test_assets::{read_test_assets_file, read_test_assets_file_as_bytes};
+use trin_validation::{
+    accumulator::PreMergeAccumulator,
+    constants::{CAPELLA_FORK_EPOCH, EPOCH_SIZE, SLOTS_PER_EPOCH},
+    header_validator::HeaderValidator,
+    summaries_verifier::SummariesVerifier,
+    test_utils::random_historical_summaries_proof,
+};
+
+/// Builds the header with proof of the terminal PoW block, verified with the pre-merge
+/// accumulator.
+fn pre_merge_header_with_proof(header_validator: &HeaderValidator) -> HeaderWithProof {
+    let file = fs::read_to_string("./src/assets/header_rlps.json").unwrap();
+    let json: Value = serde_json::from_str(&file).unwrap();
+    let raw_header = hex::decode(json["15537393"].as_str().unwrap()).unwrap();
+    let header = Header::decode(&mut raw_header.as_slice()).unwrap();
+
+    let epoch_acc = header_validator
+        .pre_merge_acc
+        .load_epoch_accumulator(header.number / EPOCH_SIZE)
+        .unwrap();
+    let proof = PreMergeAccumulator::construct_proof(&header, &epoch_acc).unwrap();
+    HeaderWithProof {
+        header,
+        proof: BlockHeaderProof::HistoricalHashes(proof),
+    }
+}
+
+/// Builds the header with proof of the block 15537397, verified with the historical roots.
+///
+/// The historical batch of the block isn't available, so one is built around the block root, and
+/// its root is set in the historical roots of the validator.
+fn merge_header_with_proof(header_validator: &mut HeaderValidator) -> HeaderWithProof {
+    let raw_block = read_test_assets_file_as_bytes(
+        "beacon/bellatrix/ValidSignedBeaconBlock/signed_beacon_block_15537397.ssz",
+    )
+    .unwrap();
+    let block = SignedBeaconBlock::from_ssz_bytes(&raw_block, ForkName::Bellatrix)
+        .unwrap()
+        .message_merge()
+        .unwrap()
+        .clone();
+
+    let payload = &block.body.execution_payload;
+    let transactions: Vec<TxEnvelope> = payload
+        .transactions
+        .iter()
+        .map(|raw_tx| TxEnvelope::decode(&mut &**raw_tx).unwrap())
+        .collect();
+    let header = Header {
+        parent_hash: payload.parent_hash,
+        ommers_hash: EMPTY_OMMER_ROOT_HASH,
+        beneficiary: payload.fee_recipient,
+        state_root: payload.state_root,
+        transactions_root: calculate_transaction_root(&transactions),
+        receipts_root: payload.receipts_root,
+        logs_bloom: Bloom::from_slice(&payload.logs_bloom),
+        difficulty: U256::ZERO,
+        number: payload.block_number,
+        gas_limit: payload.gas_limit,
+        gas_used: payload.gas_used,
+        timestamp: payload.timestamp,
+        extra_data: payload.extra_data.to_vec().into(),
+        mix_hash: payload.prev_randao,
+        nonce: B64::ZERO,
+        base_fee_per_gas: Some(payload.base_fee_per_gas.to()),
+        ..Default::default()
+    };
+    assert_eq!(header.hash_slow(), payload.block_hash);
+
+    let mut block_roots = vec![B256::random(); EPOCH_SIZE as usize];
+    block_roots[(block.slot % EPOCH_SIZE) as usize] = block.tree_hash_root();
+    let historical_batch = HistoricalBatch {
+        block_roots: block_roots.into(),
+        state_roots: vec![B256::random(); EPOCH_SIZE as usize].into(),
+    };
+    header_validator.historical_roots_acc.historical_roots[(block.slot / EPOCH_SIZE) as usize] =
+        historical_batch.tree_hash_root();
+    let proof = build_block_proof_historical_roots(block.slot, historical_batch, block);
+    HeaderWithProof {
+        header,
+        proof: BlockHeaderProof::HistoricalRoots(proof),
+    }
+}
+
+/// Builds the proof of the first Shanghai block, together with the historical summaries that it
+/// verifies against.
+///
+/// The beacon data of the block isn't available, so the proof is built from random siblings.
+fn capella_header_with_proof() -> (Header, BlockProofHistoricalSummaries, HistoricalSummaries) {
+    let file = read_test_assets_file("mainnet/block_17034871_value.json").unwrap();
+    let json: Value = serde_json::from_str(&file).unwrap();
+    let header: Header = serde_json::from_value(json["result"].clone()).unwrap();
+    let slot = 6_209_539;
+
+    let (proof, block_summary_root) = random_historical_summaries_proof(&header, slot);
+    let historical_summary = HistoricalSummary {
+        block_summary_root,
+        state_summary_root: B256::random(),
+    };
+    let period = (slot - CAPELLA_FORK_EPOCH * SLOTS_PER_EPOCH) / EPOCH_SIZE;
+    let historical_summaries = vec![historical_summary; period as usize + 1].into();
+    (header, proof, historical_summaries)
+}
+
+fn bench_header_with_proof(c: &mut Criterion) {
+    let mut group = c.benchmark_group("header_with_proof");
+
+    let mut header_validator = HeaderValidator::new();
+    let pre_merge_hwp = pre_merge_header_with_proof(&header_validator);
+    let merge_hwp = merge_header_with_proof(&mut header_validator);
+    for (fork, hwp) in [("pre-merge", pre_merge_hwp), ("merge", merge_hwp)] {
+        header_validator.validate_header_with_proof(&hwp).unwrap();
+        group.bench_with_input(BenchmarkId::new("verify", fork), &hwp, |b, hwp| {
+            b.iter(|| {
+                header_validator
+                    .validate_header_with_proof(black_box(hwp))
+                    .unwrap()
+            })
+        });
+    }
+
+    let (header, proof, historical_summaries) = capella_header_with_proof();
+    let verifier = SummariesVerifier::new(&historical_summaries);
+    verifier.verify(&proof, &header).unwrap();
+    group.bench_with_input(
+        BenchmarkId::new("verify", "capella"),
+        &(header, proof),
+        |b, (header, proof)| {
+            b.iter(|| {
+                verifier
+                    .verify(black_box(proof), black_box(header))
+                    .unwrap()
+            })
+        },
+    );
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_header_with_proof);
+criterion_main!(benches);
@@ -0,0 +1,78 @@
+use std::hint::black_box;
+
+use alloy::{consensus::Header, primitives::B256};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use ethportal_api::{
+    consensus::historical_summaries::{HistoricalSummaries, HistoricalSummary},
+    types::execution::header_with_proof::BlockProofHistoricalSummaries,
+};
+use trin_validation::{
+    constants::{CAPELLA_FORK_EPOCH, EPOCH_SIZE, SHANGHAI_BLOCK_NUMBER, SLOTS_PER_EPOCH},
+    summaries_verifier::SummariesVerifier,
+    test_utils::random_historical_summaries_proof,
+};
+
+/// Number of historical summaries periods, roughly the number of periods since Capella.
+const PERIODS: u64 = 128;
+
+/// Builds a header and a valid proof for it in every historical summaries period, together with
+/// the historical summaries that they verify against.
+fn build_proofs() -> (
+    Vec<(Header, BlockProofHistoricalSummaries)>,
+    HistoricalSummaries,
+) {
+    let capella_slot = CAPELLA_FORK_EPOCH * SLOTS_PER_EPOCH;
+    let mut historical_summaries = vec![];
+    let mut proofs = vec![];
+    for period in 0..PERIODS {
+        let header = Header {
+            number: SHANGHAI_BLOCK_NUMBER + period * EPOCH_SIZE,
+            ..Default::default()
+        };
+        let slot = capella_slot + period * EPOCH_SIZE;
+
+        let (proof, block_summary_root) = random_historical_summaries_proof(&header, slot);
+        historical_summaries.push(HistoricalSummary {
+            block_summary_root,
+            state_summary_root: B256::random(),
+        });
+        proofs.push((header, proof));
+    }
+    (proofs, historical_summaries.into())
+}
+
+fn bench_summaries_verifier(c: &mut Criterion) {
+    let (proofs, historical_summaries) = build_proofs();
+    let mut group = c.benchmark_group("summaries_verifier");
+
+    group.bench_with_input(
+        BenchmarkId::new("raw_summaries", PERIODS),
+        &historical_summaries,
+        |b, historical_summaries| {
+            b.iter(|| {
+                for (header, proof) in &proofs {
+                    SummariesVerifier::new(black_box(historical_summaries))
+                        .verify(proof, header)
+                        .unwrap();
+                }
+            })
+        },
+    );
+    group.bench_with_input(
+        BenchmarkId::new("shared_verifier", PERIODS),
+        &historical_summaries,
+        |b, historical_summaries| {
+            b.iter(|| {
+                let verifier = SummariesVerifier::new(black_box(historical_summaries));
+                for (header, proof) in &proofs {
+                    verifier.verify(proof, header).unwrap();
+                }
+            })
+        },
+    );
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_summaries_verifier);
+criterion_main!(benches);
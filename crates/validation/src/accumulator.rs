@@ -12,6 +12,7 @@ use serde::{Deserialize, Serialize};
 use ssz::Decode;
 use ssz_derive::{Decode, Encode};
 use ssz_types::{typenum, VariableList};
+use tree_hash::TreeHash as _;
 use tree_hash_derive::TreeHash;
 
 use crate::{
@@ -52,9 +53,31 @@ impl PreMergeAccumulator {
             .map_err(|err| anyhow!("Unable to decode pre-merge accumulator: {err:?}"))
     }
 
-    /// Number of the last block to be included in the accumulator
+    /// Number of the last block to be included in the accumulator, the terminal PoW block
     pub fn height(&self) -> u64 {
-        MERGE_BLOCK_NUMBER
+        MERGE_BLOCK_NUMBER - 1
+    }
+
+    /// Loads the epoch accumulator with the given index from the embedded assets, checking that
+    /// it matches the historical epoch root.
+    ///
+    /// Only a few epoch accumulators are embedded, for testing and benchmarking.
+    pub fn load_epoch_accumulator(&self, epoch_index: u64) -> anyhow::Result<EpochAccumulator> {
+        let epoch_root = self
+            .historical_epochs
+            .get(epoch_index as usize)
+            .ok_or_else(|| anyhow!("Epoch {epoch_index} is not in the accumulator"))?;
+        let raw =
+            TrinValidationAssets::get(&format!("validation_assets/epoch_accs/{epoch_root}.bin"))
+                .ok_or_else(|| anyhow!("Unable to find epoch accumulator {epoch_root}"))?;
+        let epoch_acc = EpochAccumulator::from_ssz_bytes(raw.data.as_ref())
+            .map_err(|err| anyhow!("Unable to decode epoch accumulator: {err:?}"))?;
+        if epoch_acc.tree_hash_root() != *epoch_root {
+            return Err(anyhow!(
+                "Epoch accumulator doesn't match the root {epoch_root}"
+            ));
+        }
+        Ok(epoch_acc)
     }
 
     pub(crate) fn get_epoch_index_of_header(&self, header: &Header) -> u64 {
@@ -129,3 +152,24 @@ impl PreMergeAccumulator {
         )
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_epoch_accumulator() {
+        let pre_merge_acc = PreMergeAccumulator::default();
+        let last_epoch_index = (MERGE_BLOCK_NUMBER - 1) / EPOCH_SIZE;
+
+        let epoch_acc = pre_merge_acc
+            .load_epoch_accumulator(last_epoch_index)
+            .unwrap();
+        assert_eq!(epoch_acc.len(), 5362);
+        assert!(pre_merge_acc.load_epoch_accumulator(1).is_err());
+        assert!(pre_merge_acc
+            .load_epoch_accumulator(last_epoch_index + 1)
+            .is_err());
+    }
+}
@@ -0,0 +1,87 @@
+use std::future::Future;
+
+use ethportal_api::consensus::historical_summaries::HistoricalSummaries;
+use thiserror::Error;
+
+use crate::summaries_verifier::SummariesVerifier;
+
+/// An error fetching or updating the historical anchors.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum AnchorError {
+    /// The request to the anchor source failed.
+    #[error("failed to request the anchors: {0}")]
+    Request(String),
+    /// The anchor source returned a response that couldn't be decoded.
+    #[error("invalid anchors response: {0}")]
+    InvalidResponse(String),
+    /// The historical summary isn't for the next period of the anchors.
+    #[error("unexpected historical summaries period {period}, expected: {expected}")]
+    UnexpectedPeriod { period: u64, expected: u64 },
+    /// The historical summary doesn't match the one already known for the period.
+    #[error("conflicting historical summary for period {period}")]
+    ConflictingSummary { period: u64 },
+}
+
+/// Provides the historical anchors needed to verify post-merge headers, e.g. from a trusted
+/// Beacon API node.
+pub trait AnchorProvider {
+    /// Returns the `historical_summaries` of the latest finalized beacon state.
+    fn historical_summaries(
+        &self,
+    ) -> impl Future<Output = Result<HistoricalSummaries, AnchorError>> + Send;
+}
+
+impl SummariesVerifier {
+    /// Creates a verifier from the `historical_summaries` fetched from the provider.
+    pub async fn from_anchor_provider(provider: &impl AnchorProvider) -> Result<Self, AnchorError> {
+        Ok(Self::new(&provider.historical_summaries().await?))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use alloy::primitives::B256;
+    use ethportal_api::consensus::historical_summaries::HistoricalSummary;
+
+    use super::*;
+
+    struct FailingProvider;
+
+    impl AnchorProvider for FailingProvider {
+        async fn historical_summaries(&self) -> Result<HistoricalSummaries, AnchorError> {
+            Err(AnchorError::Request("connection refused".to_string()))
+        }
+    }
+
+    struct StaticProvider(HistoricalSummaries);
+
+    impl AnchorProvider for StaticProvider {
+        async fn historical_summaries(&self) -> Result<HistoricalSummaries, AnchorError> {
+            Ok(self.0.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn summaries_verifier_from_anchor_provider() {
+        let historical_summaries: HistoricalSummaries = vec![
+            HistoricalSummary {
+                block_summary_root: B256::random(),
+                state_summary_root: B256::random(),
+            };
+            2
+        ]
+        .into();
+
+        assert_eq!(
+            SummariesVerifier::from_anchor_provider(&StaticProvider(historical_summaries.clone()))
+                .await
+                .unwrap(),
+            SummariesVerifier::new(&historical_summaries)
+        );
+        assert_eq!(
+            SummariesVerifier::from_anchor_provider(&FailingProvider).await,
+            Err(AnchorError::Request("connection refused".to_string()))
+        );
+    }
+}
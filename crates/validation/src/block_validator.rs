@@ -0,0 +1,180 @@
+use alloy::{consensus::Header, primitives::B256};
+use ethportal_api::types::execution::{block_body::BlockBody, receipts::Receipts};
+use thiserror::Error;
+
+/// An error validating the block body and receipts against the block header.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum BlockValidationError {
+    #[error("invalid uncles root, expected: {expected}, actual: {actual}")]
+    InvalidUnclesRoot { expected: B256, actual: B256 },
+
+    #[error("invalid transactions root, expected: {expected}, actual: {actual}")]
+    InvalidTransactionsRoot { expected: B256, actual: B256 },
+
+    /// The withdrawals root doesn't match, or only one of the header and the body has
+    /// withdrawals.
+    #[error("invalid withdrawals root, expected: {expected:?}, actual: {actual:?}")]
+    InvalidWithdrawalsRoot {
+        expected: Option<B256>,
+        actual: Option<B256>,
+    },
+
+    #[error("invalid receipts root, expected: {expected}, actual: {actual}")]
+    InvalidReceiptsRoot { expected: B256, actual: B256 },
+}
+
+/// Validates that the block body and receipts match the roots committed to in the header.
+pub fn validate_full_block(
+    header: &Header,
+    body: &BlockBody,
+    receipts: &Receipts,
+) -> Result<(), BlockValidationError> {
+    let uncles_root = body.calculate_ommers_root();
+    if uncles_root != header.ommers_hash {
+        return Err(BlockValidationError::InvalidUnclesRoot {
+            expected: header.ommers_hash,
+            actual: uncles_root,
+        });
+    }
+
+    let transactions_root = body.transactions_root();
+    if transactions_root != header.transactions_root {
+        return Err(BlockValidationError::InvalidTransactionsRoot {
+            expected: header.transactions_root,
+            actual: transactions_root,
+        });
+    }
+
+    let withdrawals_root = body.calculate_withdrawals_root();
+    if withdrawals_root != header.withdrawals_root {
+        return Err(BlockValidationError::InvalidWithdrawalsRoot {
+            expected: header.withdrawals_root,
+            actual: withdrawals_root,
+        });
+    }
+
+    let receipts_root = receipts.root();
+    if receipts_root != header.receipts_root {
+        return Err(BlockValidationError::InvalidReceiptsRoot {
+            expected: header.receipts_root,
+            actual: receipts_root,
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use alloy::{
+        consensus::{BlockBody as AlloyBlockBody, Header},
+        eips::eip4895::Withdrawals,
+    };
+    use serde_json::Value;
+    use trin_utils::test_assets::read_test_assets_file;
+
+    use super::*;
+
+    /// Reads the header, body and receipts of the post-Shanghai block 19433902.
+    fn read_block_19433902() -> (Header, BlockBody, Receipts) {
+        let file = read_test_assets_file("mainnet/block_19433902_value.json").unwrap();
+        let json: Value = serde_json::from_str(&file).unwrap();
+        let block = &json["result"];
+        let header: Header = serde_json::from_value(block.clone()).unwrap();
+        let body = BlockBody(AlloyBlockBody {
+            transactions: serde_json::from_value(block["transactions"].clone()).unwrap(),
+            ommers: vec![],
+            withdrawals: Some(serde_json::from_value(block["withdrawals"].clone()).unwrap()),
+        });
+
+        let file = read_test_assets_file("infura_batch/receipts-19433902.json").unwrap();
+        let receipts: Receipts = serde_json::from_str(&file).unwrap();
+
+        (header, body, receipts)
+    }
+
+    #[test]
+    fn validate_block() {
+        let (header, body, receipts) = read_block_19433902();
+        validate_full_block(&header, &body, &receipts).unwrap();
+    }
+
+    #[test]
+    fn invalidate_block_with_invalid_uncles() {
+        let (header, mut body, receipts) = read_block_19433902();
+        body.0.ommers.push(Header::default());
+
+        assert!(matches!(
+            validate_full_block(&header, &body, &receipts),
+            Err(BlockValidationError::InvalidUnclesRoot { .. })
+        ));
+    }
+
+    #[test]
+    fn invalidate_block_with_invalid_transactions() {
+        let (header, mut body, receipts) = read_block_19433902();
+        body.0.transactions.pop();
+
+        assert!(matches!(
+            validate_full_block(&header, &body, &receipts),
+            Err(BlockValidationError::InvalidTransactionsRoot { .. })
+        ));
+    }
+
+    #[test]
+    fn invalidate_block_with_invalid_withdrawals() {
+        let (header, mut body, receipts) = read_block_19433902();
+        let mut withdrawals = body.0.withdrawals.take().unwrap();
+        withdrawals.0[0].amount += 1;
+        body.0.withdrawals = Some(withdrawals);
+
+        assert!(matches!(
+            validate_full_block(&header, &body, &receipts),
+            Err(BlockValidationError::InvalidWithdrawalsRoot {
+                expected: Some(_),
+                actual: Some(_),
+            })
+        ));
+    }
+
+    #[test]
+    fn invalidate_block_with_missing_withdrawals() {
+        let (header, mut body, receipts) = read_block_19433902();
+        body.0.withdrawals = None;
+
+        assert_eq!(
+            validate_full_block(&header, &body, &receipts),
+            Err(BlockValidationError::InvalidWithdrawalsRoot {
+                expected: header.withdrawals_root,
+                actual: None,
+            })
+        );
+    }
+
+    #[test]
+    fn invalidate_block_with_unexpected_withdrawals() {
+        let (mut header, mut body, receipts) = read_block_19433902();
+        header.withdrawals_root = None;
+        body.0.withdrawals = Some(Withdrawals::default());
+
+        assert!(matches!(
+            validate_full_block(&header, &body, &receipts),
+            Err(BlockValidationError::InvalidWithdrawalsRoot {
+                expected: None,
+                actual: Some(_),
+            })
+        ));
+    }
+
+    #[test]
+    fn invalidate_block_with_invalid_receipts() {
+        let (header, body, mut receipts) = read_block_19433902();
+        receipts.0.pop();
+
+        assert!(matches!(
+            validate_full_block(&header, &body, &receipts),
+            Err(BlockValidationError::InvalidReceiptsRoot { .. })
+        ));
+    }
+}
@@ -0,0 +1,245 @@
+use alloy::{consensus::Header, primitives::B256};
+use ethportal_api::{
+    consensus::historical_summaries::HistoricalSummaries,
+    types::execution::header_with_proof::{
+        BlockHeaderProof, BlockProofHistoricalRoots, BlockProofHistoricalSummaries,
+        HeaderWithProof, ProofError,
+    },
+};
+
+use crate::{
+    constants::{HISTORICAL_ROOTS_PROOF_DEPTH, HISTORICAL_SUMMARIES_PROOF_DEPTH},
+    header_validator::HeaderValidator,
+};
+
+/// The anchor that the beacon block is proven against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompactProofType {
+    /// Merge -> Capella, with a beacon block proof of 14 hashes.
+    HistoricalRoots,
+    /// Post-Capella, with a beacon block proof of 13 hashes.
+    HistoricalSummaries,
+}
+
+impl CompactProofType {
+    fn beacon_block_proof_length(&self) -> usize {
+        match self {
+            Self::HistoricalRoots => HISTORICAL_ROOTS_PROOF_DEPTH,
+            Self::HistoricalSummaries => HISTORICAL_SUMMARIES_PROOF_DEPTH,
+        }
+    }
+}
+
+/// A post-merge header with its execution block and beacon block proofs combined into a single
+/// branch, from the header hash up to the historical anchor.
+///
+/// The two proofs are levels of the same tree, so the `beacon_block_root` between them is implied
+/// by the execution block proof and is left out of the compact proof.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompactHeaderWithProof {
+    pub header: Header,
+    pub proof_type: CompactProofType,
+    /// The execution block proof, followed by the beacon block proof.
+    pub branch: Vec<B256>,
+    pub slot: u64,
+}
+
+impl CompactHeaderWithProof {
+    /// Compacts the proof of a post-merge header.
+    pub fn from_header_with_proof(hwp: &HeaderWithProof) -> Result<Self, ProofError> {
+        let (proof_type, execution_block_proof, beacon_block_proof, slot) = match &hwp.proof {
+            BlockHeaderProof::HistoricalHashes(_) => {
+                return Err(ProofError::UnexpectedProofType {
+                    block_number: hwp.header.number,
+                })
+            }
+            BlockHeaderProof::HistoricalRoots(proof) => (
+                CompactProofType::HistoricalRoots,
+                &proof.execution_block_proof[..],
+                &proof.beacon_block_proof[..],
+                proof.slot,
+            ),
+            BlockHeaderProof::HistoricalSummaries(proof) => (
+                CompactProofType::HistoricalSummaries,
+                &proof.execution_block_proof[..],
+                &proof.beacon_block_proof[..],
+                proof.slot,
+            ),
+        };
+        Ok(Self {
+            header: hwp.header.clone(),
+            proof_type,
+            branch: [execution_block_proof, beacon_block_proof].concat(),
+            slot,
+        })
+    }
+
+    /// Expands the compact proof, reconstructing the `beacon_block_root` from the execution block
+    /// proof.
+    pub fn to_header_with_proof(&self) -> Result<HeaderWithProof, ProofError> {
+        let (execution_block_proof_length, _) =
+            HeaderValidator::execution_block_proof_layout(self.header.timestamp);
+        let expected_length =
+            execution_block_proof_length + self.proof_type.beacon_block_proof_length();
+        if self.branch.len() != expected_length {
+            return Err(ProofError::InvalidCompactProofLength {
+                length: self.branch.len(),
+            });
+        }
+        let (execution_block_proof, beacon_block_proof) =
+            self.branch.split_at(execution_block_proof_length);
+        let beacon_block_root = HeaderValidator::reconstruct_beacon_block_root(
+            self.header.hash_slow(),
+            self.header.timestamp,
+            execution_block_proof.iter().copied(),
+        )?;
+
+        let proof = match self.proof_type {
+            CompactProofType::HistoricalRoots => {
+                BlockHeaderProof::HistoricalRoots(BlockProofHistoricalRoots {
+                    beacon_block_proof: beacon_block_proof.to_vec().into(),
+                    beacon_block_root,
+                    execution_block_proof: execution_block_proof.to_vec().into(),
+                    slot: self.slot,
+                })
+            }
+            CompactProofType::HistoricalSummaries => {
+                BlockHeaderProof::HistoricalSummaries(BlockProofHistoricalSummaries {
+                    beacon_block_proof: beacon_block_proof.to_vec().into(),
+                    beacon_block_root,
+                    execution_block_proof: execution_block_proof.to_vec().into(),
+                    slot: self.slot,
+                })
+            }
+        };
+        Ok(HeaderWithProof {
+            header: self.header.clone(),
+            proof,
+        })
+    }
+
+    /// Expands the compact proof and verifies it, using the `historical_summaries` to verify
+    /// post-Capella headers.
+    pub fn verify(
+        &self,
+        header_validator: &HeaderValidator,
+        historical_summaries: &HistoricalSummaries,
+    ) -> Result<(), ProofError> {
+        header_validator
+            .verify_header_with_proof(&self.to_header_with_proof()?, historical_summaries)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use ethportal_api::consensus::historical_summaries::HistoricalSummary;
+
+    use super::*;
+    use crate::{
+        accumulator::PreMergeAccumulator,
+        constants::{MERGE_BLOCK_NUMBER, SHANGHAI_BLOCK_NUMBER},
+        historical_roots_acc::HistoricalRootsAccumulator,
+        test_utils::{random_historical_roots_proof, random_historical_summaries_proof},
+    };
+
+    /// Builds a valid header with proof of the given type, and the root of the anchor that it
+    /// verifies against.
+    fn build_header_with_proof(proof_type: CompactProofType, slot: u64) -> (HeaderWithProof, B256) {
+        let block_number = match proof_type {
+            CompactProofType::HistoricalRoots => MERGE_BLOCK_NUMBER,
+            CompactProofType::HistoricalSummaries => SHANGHAI_BLOCK_NUMBER,
+        };
+        let header = Header {
+            number: block_number,
+            parent_hash: B256::random(),
+            ..Default::default()
+        };
+        let (proof, anchor_root) = match proof_type {
+            CompactProofType::HistoricalRoots => {
+                let (proof, historical_root) = random_historical_roots_proof(&header, slot);
+                (BlockHeaderProof::HistoricalRoots(proof), historical_root)
+            }
+            CompactProofType::HistoricalSummaries => {
+                let (proof, block_summary_root) = random_historical_summaries_proof(&header, slot);
+                (
+                    BlockHeaderProof::HistoricalSummaries(proof),
+                    block_summary_root,
+                )
+            }
+        };
+        (HeaderWithProof { header, proof }, anchor_root)
+    }
+
+    #[test]
+    fn historical_summaries_compact_round_trip() {
+        // Slot 6209538 belongs to historical summaries period 0
+        let (hwp, block_summary_root) =
+            build_header_with_proof(CompactProofType::HistoricalSummaries, 6_209_538);
+        let historical_summaries: HistoricalSummaries = vec![HistoricalSummary {
+            block_summary_root,
+            state_summary_root: B256::random(),
+        }]
+        .into();
+        let header_validator = HeaderValidator::default();
+        let BlockHeaderProof::HistoricalSummaries(proof) = &hwp.proof else {
+            unreachable!()
+        };
+        header_validator
+            .verify_post_capella_header(&hwp.header, proof, &historical_summaries)
+            .unwrap();
+
+        let compact_hwp = CompactHeaderWithProof::from_header_with_proof(&hwp).unwrap();
+        assert_eq!(compact_hwp.branch.len(), 11 + 13);
+        compact_hwp
+            .verify(&header_validator, &historical_summaries)
+            .unwrap();
+        assert_eq!(compact_hwp.to_header_with_proof().unwrap(), hwp);
+    }
+
+    #[test]
+    fn historical_roots_compact_round_trip() {
+        // Slot 4700013 belongs to the historical root 573
+        let (hwp, historical_root) =
+            build_header_with_proof(CompactProofType::HistoricalRoots, 4_700_013);
+        let mut historical_roots = vec![B256::random(); 574];
+        historical_roots[573] = historical_root;
+        let header_validator = HeaderValidator {
+            pre_merge_acc: PreMergeAccumulator::default(),
+            historical_roots_acc: HistoricalRootsAccumulator {
+                historical_roots: historical_roots.into(),
+            },
+        };
+        header_validator.validate_header_with_proof(&hwp).unwrap();
+
+        let compact_hwp = CompactHeaderWithProof::from_header_with_proof(&hwp).unwrap();
+        assert_eq!(compact_hwp.branch.len(), 11 + 14);
+        compact_hwp
+            .verify(&header_validator, &HistoricalSummaries::default())
+            .unwrap();
+        assert_eq!(compact_hwp.to_header_with_proof().unwrap(), hwp);
+    }
+
+    #[test]
+    fn compact_proof_with_invalid_branch() {
+        let (hwp, block_summary_root) =
+            build_header_with_proof(CompactProofType::HistoricalSummaries, 6_209_538);
+        let historical_summaries: HistoricalSummaries = vec![HistoricalSummary {
+            block_summary_root,
+            state_summary_root: B256::random(),
+        }]
+        .into();
+        let mut compact_hwp = CompactHeaderWithProof::from_header_with_proof(&hwp).unwrap();
+
+        compact_hwp.branch[3] = B256::random();
+        assert!(compact_hwp
+            .verify(&HeaderValidator::default(), &historical_summaries)
+            .is_err());
+
+        compact_hwp.branch.pop();
+        assert_eq!(
+            compact_hwp.to_header_with_proof(),
+            Err(ProofError::InvalidCompactProofLength { length: 23 })
+        );
+    }
+}
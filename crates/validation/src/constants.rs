@@ -1,12 +1,12 @@
 // Execution Layer hard forks https://ethereum.org/en/history/
+pub use ethportal_api::types::execution::header::{
+    BYZANTIUM_BLOCK_NUMBER, CONSTANTINOPLE_BLOCK_NUMBER, LONDON_BLOCK_NUMBER, MERGE_BLOCK_NUMBER,
+};
+pub const PRAGUE_BLOCK_NUMBER: u64 = 22_431_084;
 pub const CANCUN_BLOCK_NUMBER: u64 = 19_426_587;
 pub const SHANGHAI_BLOCK_NUMBER: u64 = 17_034_870;
-pub const MERGE_BLOCK_NUMBER: u64 = 15_537_394;
-pub const LONDON_BLOCK_NUMBER: u64 = 12_965_000;
 pub const BERLIN_BLOCK_NUMBER: u64 = 12_244_000;
 pub const ISTANBUL_BLOCK_NUMBER: u64 = 9_069_000;
-pub const CONSTANTINOPLE_BLOCK_NUMBER: u64 = 7_280_000;
-pub const BYZANTIUM_BLOCK_NUMBER: u64 = 4_370_000;
 pub const HOMESTEAD_BLOCK_NUMBER: u64 = 1_150_000;
 pub const CAPELLA_FORK_EPOCH: u64 = 194_048;
 pub const SLOTS_PER_EPOCH: u64 = 32;
@@ -18,6 +18,25 @@ pub const DEFAULT_PRE_MERGE_ACC_HASH: &str =
 /// Max number of blocks / epoch = 2 ** 13
 pub const EPOCH_SIZE: u64 = 8192;
 
+/// Depth of the proof that a pre-merge header is part of its epoch accumulator.
+pub const HISTORICAL_HASHES_PROOF_DEPTH: usize = 15;
+
+/// Depth and generalized index of the proof that the execution block header is part of the
+/// beacon block, until Deneb.
+pub const EXECUTION_BLOCK_PROOF_DEPTH: usize = 11;
+pub const EXECUTION_BLOCK_PROOF_GEN_INDEX: usize = 3228;
+
+/// Depth and generalized index of the proof that the execution block header is part of the
+/// beacon block, from Deneb.
+pub const EXECUTION_BLOCK_PROOF_DEPTH_DENEB: usize = 12;
+pub const EXECUTION_BLOCK_PROOF_GEN_INDEX_DENEB: usize = 6444;
+
+/// Depth of the proof that a beacon block is part of its `historical_roots` entry.
+pub const HISTORICAL_ROOTS_PROOF_DEPTH: usize = 14;
+
+/// Depth of the proof that a beacon block is part of its `historical_summaries` period.
+pub const HISTORICAL_SUMMARIES_PROOF_DEPTH: usize = 13;
+
 // Max number of epochs = 2 ** 17
 // const MAX_HISTORICAL_EPOCHS: usize = 131072;
 
@@ -0,0 +1,153 @@
+use alloy::{
+    consensus::Header,
+    primitives::{B256, U256},
+};
+use anyhow::{anyhow, ensure};
+use ethportal_api::types::execution::accumulator::{EpochAccumulator, HeaderRecord};
+use tree_hash::TreeHash;
+
+use crate::{accumulator::PreMergeAccumulator, constants::EPOCH_SIZE};
+
+/// Proof that a contiguous segment of pre-merge headers makes up an epoch of the pre-merge
+/// accumulator.
+///
+/// The segment must start at the first block of the epoch. It only reconstructs the epoch root
+/// if it covers the whole epoch, or everything up to the merge for the last (partial) epoch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EpochHeaderProof {
+    pub epoch_index: u64,
+    pub headers: Vec<Header>,
+    pub epoch_root: B256,
+    /// Total difficulty of the chain before the first block of the epoch.
+    pub initial_total_difficulty: U256,
+}
+
+impl EpochHeaderProof {
+    /// Verifies that the epoch accumulator reconstructed from the headers matches the
+    /// `epoch_root`, and that the `epoch_root` is part of the pre-merge accumulator.
+    pub fn verify(&self, pre_merge_acc: &PreMergeAccumulator) -> anyhow::Result<()> {
+        let historical_epoch = pre_merge_acc
+            .historical_epochs
+            .get(self.epoch_index as usize)
+            .ok_or_else(|| anyhow!("Epoch {} is not in the accumulator", self.epoch_index))?;
+        ensure!(
+            *historical_epoch == self.epoch_root,
+            "Epoch root doesn't match the accumulator, expected: {historical_epoch}, actual: {}",
+            self.epoch_root
+        );
+
+        let epoch_accumulator = self.epoch_accumulator()?;
+        let epoch_root = epoch_accumulator.tree_hash_root();
+        ensure!(
+            epoch_root == self.epoch_root,
+            "Headers don't match the epoch root, expected: {}, actual: {epoch_root}",
+            self.epoch_root
+        );
+        Ok(())
+    }
+
+    /// Builds the epoch accumulator from the headers, checking that they form a contiguous chain
+    /// starting at the first block of the epoch.
+    fn epoch_accumulator(&self) -> anyhow::Result<EpochAccumulator> {
+        ensure!(
+            self.headers.len() as u64 <= EPOCH_SIZE,
+            "Too many headers for an epoch: {}",
+            self.headers.len()
+        );
+        let mut total_difficulty = self.initial_total_difficulty;
+        let mut header_records = Vec::with_capacity(self.headers.len());
+        let mut parent_hash = None;
+        for (index, header) in self.headers.iter().enumerate() {
+            let expected_number = self.epoch_index * EPOCH_SIZE + index as u64;
+            ensure!(
+                header.number == expected_number,
+                "Unexpected header number, expected: {expected_number}, actual: {}",
+                header.number
+            );
+            if let Some(parent_hash) = parent_hash {
+                ensure!(
+                    header.parent_hash == parent_hash,
+                    "Header {} is not a child of the previous header",
+                    header.number
+                );
+            }
+            let block_hash = header.hash_slow();
+            total_difficulty += header.difficulty;
+            header_records.push(HeaderRecord {
+                block_hash,
+                total_difficulty,
+            });
+            parent_hash = Some(block_hash);
+        }
+        Ok(EpochAccumulator::from(header_records))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    const EPOCH_INDEX: u64 = 2;
+
+    /// Builds a chain of headers starting at the first block of the epoch, and an accumulator
+    /// containing the root of the resulting (partial) epoch.
+    fn build_partial_epoch(length: usize) -> (EpochHeaderProof, PreMergeAccumulator) {
+        let mut headers: Vec<Header> = vec![];
+        for index in 0..length as u64 {
+            headers.push(Header {
+                number: EPOCH_INDEX * EPOCH_SIZE + index,
+                parent_hash: headers.last().map(Header::hash_slow).unwrap_or_default(),
+                difficulty: U256::from(1_000 + index),
+                ..Default::default()
+            });
+        }
+        let mut proof = EpochHeaderProof {
+            epoch_index: EPOCH_INDEX,
+            headers,
+            epoch_root: B256::ZERO,
+            initial_total_difficulty: U256::from(50_000),
+        };
+        proof.epoch_root = proof.epoch_accumulator().unwrap().tree_hash_root();
+        let pre_merge_acc = PreMergeAccumulator {
+            historical_epochs: vec![B256::random(), B256::random(), proof.epoch_root].into(),
+        };
+        (proof, pre_merge_acc)
+    }
+
+    #[test]
+    fn verify_partial_epoch() {
+        let (proof, pre_merge_acc) = build_partial_epoch(100);
+        proof.verify(&pre_merge_acc).unwrap();
+    }
+
+    #[test]
+    fn verify_partial_epoch_with_missing_header() {
+        let (mut proof, pre_merge_acc) = build_partial_epoch(100);
+        proof.headers.pop();
+        assert!(proof.verify(&pre_merge_acc).is_err());
+    }
+
+    #[test]
+    fn verify_partial_epoch_with_invalid_total_difficulty() {
+        let (mut proof, pre_merge_acc) = build_partial_epoch(100);
+        proof.initial_total_difficulty += U256::from(1);
+        assert!(proof.verify(&pre_merge_acc).is_err());
+    }
+
+    #[test]
+    fn verify_partial_epoch_with_non_contiguous_headers() {
+        let (mut proof, pre_merge_acc) = build_partial_epoch(100);
+        proof.headers.remove(50);
+        let error = proof.verify(&pre_merge_acc).unwrap_err();
+        assert!(error.to_string().contains("Unexpected header number"));
+    }
+
+    #[test]
+    fn verify_epoch_root_not_in_accumulator() {
+        let (mut proof, pre_merge_acc) = build_partial_epoch(100);
+        proof.epoch_root = pre_merge_acc.historical_epochs[0];
+        let error = proof.verify(&pre_merge_acc).unwrap_err();
+        assert!(error.to_string().contains("Epoch root doesn't match"));
+    }
+}
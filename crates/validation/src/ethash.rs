@@ -0,0 +1,284 @@
+//! Ethash proof-of-work verification of pre-merge headers.
+//!
+//! Only light verification is implemented: the dataset items accessed by the proof-of-work are
+//! computed from the cache of the epoch, so the full dataset (DAG) is never generated.
+//!
+//! Specification: https://ethereum.org/en/developers/docs/consensus-mechanisms/pow/mining/mining-algorithms/ethash/
+use alloy::{
+    consensus::Header,
+    primitives::{keccak256, B256, U256},
+    rlp::{self, Encodable},
+};
+use sha3::{Digest, Keccak512};
+use thiserror::Error;
+
+const EPOCH_LENGTH: u64 = 30_000;
+const DATASET_BYTES_INIT: u64 = 1 << 30;
+const DATASET_BYTES_GROWTH: u64 = 1 << 23;
+const CACHE_BYTES_INIT: u64 = 1 << 24;
+const CACHE_BYTES_GROWTH: u64 = 1 << 17;
+const MIX_BYTES: u64 = 128;
+const HASH_BYTES: u64 = 64;
+const HASH_WORDS: usize = 16;
+const MIX_WORDS: usize = 32;
+const DATASET_PARENTS: u32 = 256;
+const CACHE_ROUNDS: usize = 3;
+const ACCESSES: u32 = 64;
+const FNV_PRIME: u32 = 0x01000193;
+
+/// An error verifying the proof-of-work of a header.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum PowError {
+    /// The header is post-merge, so it doesn't have a proof-of-work.
+    #[error("header {block_number} has zero difficulty")]
+    ZeroDifficulty { block_number: u64 },
+    /// The cache is for a different epoch than the header.
+    #[error("cache is for epoch {cache_epoch}, but the header is in epoch {header_epoch}")]
+    EpochMismatch { cache_epoch: u64, header_epoch: u64 },
+    /// The mix digest computed from the nonce doesn't match the header's `mix_hash`.
+    #[error("invalid mix hash, expected: {expected}, actual: {actual}")]
+    InvalidMixHash { expected: B256, actual: B256 },
+    /// The proof-of-work result doesn't meet the header's difficulty.
+    #[error("proof-of-work doesn't meet the difficulty {difficulty}")]
+    DifficultyNotMet { difficulty: U256 },
+}
+
+/// The Ethash cache of an epoch, used for light verification of its headers.
+///
+/// Generating the cache is expensive (it is between 16 MiB and 100+ MiB in size, depending on
+/// the epoch), so it should be reused for all the headers of the same epoch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LightCache {
+    epoch: u64,
+    cache: Vec<[u32; HASH_WORDS]>,
+}
+
+impl LightCache {
+    /// Generates the cache of the epoch of the given block.
+    pub fn new(block_number: u64) -> Self {
+        let epoch = block_number / EPOCH_LENGTH;
+        let seed = (0..epoch).fold(B256::ZERO, |seed, _| keccak256(seed));
+
+        let n = (cache_size(epoch) / HASH_BYTES) as usize;
+        let mut cache = Vec::with_capacity(n);
+        cache.push(keccak512(seed.as_slice()));
+        for i in 1..n {
+            cache.push(keccak512(&words_to_bytes(&cache[i - 1])));
+        }
+        for _ in 0..CACHE_ROUNDS {
+            for i in 0..n {
+                let v = cache[i][0] as usize % n;
+                let mut item = cache[(i + n - 1) % n];
+                for (word, other) in item.iter_mut().zip(cache[v]) {
+                    *word ^= other;
+                }
+                cache[i] = keccak512(&words_to_bytes(&item));
+            }
+        }
+
+        Self { epoch, cache }
+    }
+
+    /// Verifies the proof-of-work of the header, i.e. that its `mix_hash` and `nonce` are valid
+    /// and meet its `difficulty`.
+    pub fn verify_pow(&self, header: &Header) -> Result<(), PowError> {
+        if header.difficulty.is_zero() {
+            return Err(PowError::ZeroDifficulty {
+                block_number: header.number,
+            });
+        }
+        let header_epoch = header.number / EPOCH_LENGTH;
+        if header_epoch != self.epoch {
+            return Err(PowError::EpochMismatch {
+                cache_epoch: self.epoch,
+                header_epoch,
+            });
+        }
+
+        let (mix_digest, result) = self.hashimoto_light(seal_hash(header), header.nonce.into());
+        if mix_digest != header.mix_hash {
+            return Err(PowError::InvalidMixHash {
+                expected: header.mix_hash,
+                actual: mix_digest,
+            });
+        }
+        // The result must be lower than or equal to 2^256 / difficulty
+        let target = U256::MAX / header.difficulty;
+        if U256::from_be_bytes(result.0) > target {
+            return Err(PowError::DifficultyNotMet {
+                difficulty: header.difficulty,
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns the mix digest and the result of the proof-of-work.
+    fn hashimoto_light(&self, seal_hash: B256, nonce: u64) -> (B256, B256) {
+        let mut seed = seal_hash.to_vec();
+        seed.extend_from_slice(&nonce.to_le_bytes());
+        let seed = keccak512(&seed);
+
+        let mut mix = [0u32; MIX_WORDS];
+        mix[..HASH_WORDS].copy_from_slice(&seed);
+        mix[HASH_WORDS..].copy_from_slice(&seed);
+
+        let pages = (dataset_size(self.epoch) / MIX_BYTES) as u32;
+        for i in 0..ACCESSES {
+            let page = fnv(i ^ seed[0], mix[i as usize % MIX_WORDS]) % pages;
+            let first_item = self.dataset_item(2 * page);
+            let second_item = self.dataset_item(2 * page + 1);
+            for (word, data) in mix.iter_mut().zip(first_item.iter().chain(&second_item)) {
+                *word = fnv(*word, *data);
+            }
+        }
+
+        let compressed_mix: Vec<u32> = mix
+            .chunks(4)
+            .map(|words| fnv(fnv(fnv(words[0], words[1]), words[2]), words[3]))
+            .collect();
+        let mix_digest = B256::from_slice(&words_to_bytes(&compressed_mix));
+
+        let mut result = words_to_bytes(&seed);
+        result.extend_from_slice(mix_digest.as_slice());
+        (mix_digest, keccak256(result))
+    }
+
+    /// Computes the item of the full dataset from the cache.
+    fn dataset_item(&self, index: u32) -> [u32; HASH_WORDS] {
+        let n = self.cache.len();
+        let mut mix = self.cache[index as usize % n];
+        mix[0] ^= index;
+        let mut mix = keccak512(&words_to_bytes(&mix));
+        for j in 0..DATASET_PARENTS {
+            let parent = fnv(index ^ j, mix[j as usize % HASH_WORDS]) as usize % n;
+            for (word, data) in mix.iter_mut().zip(self.cache[parent]) {
+                *word = fnv(*word, data);
+            }
+        }
+        keccak512(&words_to_bytes(&mix))
+    }
+}
+
+/// Verifies the proof-of-work of the header, generating the cache of its epoch.
+///
+/// When verifying many headers, use [LightCache::verify_pow] to reuse the cache instead.
+pub fn verify_pow(header: &Header) -> Result<(), PowError> {
+    LightCache::new(header.number).verify_pow(header)
+}
+
+/// Returns the hash of the header without the `mix_hash` and `nonce`, which is the input of the
+/// proof-of-work.
+fn seal_hash(header: &Header) -> B256 {
+    let mut payload = vec![];
+    header.parent_hash.encode(&mut payload);
+    header.ommers_hash.encode(&mut payload);
+    header.beneficiary.encode(&mut payload);
+    header.state_root.encode(&mut payload);
+    header.transactions_root.encode(&mut payload);
+    header.receipts_root.encode(&mut payload);
+    header.logs_bloom.encode(&mut payload);
+    header.difficulty.encode(&mut payload);
+    header.number.encode(&mut payload);
+    header.gas_limit.encode(&mut payload);
+    header.gas_used.encode(&mut payload);
+    header.timestamp.encode(&mut payload);
+    header.extra_data.encode(&mut payload);
+    if let Some(base_fee_per_gas) = header.base_fee_per_gas {
+        base_fee_per_gas.encode(&mut payload);
+    }
+
+    let mut buf = vec![];
+    rlp::Header {
+        list: true,
+        payload_length: payload.len(),
+    }
+    .encode(&mut buf);
+    buf.extend_from_slice(&payload);
+    keccak256(buf)
+}
+
+fn cache_size(epoch: u64) -> u64 {
+    let mut size = CACHE_BYTES_INIT + CACHE_BYTES_GROWTH * epoch - HASH_BYTES;
+    while !is_prime(size / HASH_BYTES) {
+        size -= 2 * HASH_BYTES;
+    }
+    size
+}
+
+fn dataset_size(epoch: u64) -> u64 {
+    let mut size = DATASET_BYTES_INIT + DATASET_BYTES_GROWTH * epoch - MIX_BYTES;
+    while !is_prime(size / MIX_BYTES) {
+        size -= 2 * MIX_BYTES;
+    }
+    size
+}
+
+fn is_prime(n: u64) -> bool {
+    n >= 2 && (2..).take_while(|i| i * i <= n).all(|i| n % i != 0)
+}
+
+fn fnv(v1: u32, v2: u32) -> u32 {
+    v1.wrapping_mul(FNV_PRIME) ^ v2
+}
+
+fn keccak512(data: &[u8]) -> [u32; HASH_WORDS] {
+    let hash = Keccak512::digest(data);
+    let mut words = [0u32; HASH_WORDS];
+    for (word, bytes) in words.iter_mut().zip(hash.chunks_exact(4)) {
+        *word = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    }
+    words
+}
+
+fn words_to_bytes(words: &[u32]) -> Vec<u8> {
+    words.iter().flat_map(|word| word.to_le_bytes()).collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use alloy::{primitives::B64, rlp::Decodable};
+    use ethportal_api::utils::bytes::hex_decode;
+    use serde_json::Value;
+
+    use super::*;
+
+    fn get_header(number: u64) -> Header {
+        let file = std::fs::read_to_string("./src/assets/header_rlps.json").unwrap();
+        let json: Value = serde_json::from_str(&file).unwrap();
+        let raw_header = json[number.to_string()].as_str().unwrap();
+        Header::decode(&mut hex_decode(raw_header).unwrap().as_slice()).unwrap()
+    }
+
+    #[test]
+    fn sizes_of_first_epoch() {
+        // Values from the Ethash specification's lookup tables
+        assert_eq!(cache_size(0), 16_776_896);
+        assert_eq!(dataset_size(0), 1_073_739_904);
+    }
+
+    #[test]
+    fn verify_pow_of_pre_merge_headers() {
+        let cache = LightCache::new(1);
+        for block_number in [1, 2] {
+            cache.verify_pow(&get_header(block_number)).unwrap();
+        }
+
+        let header = Header {
+            nonce: B64::ZERO,
+            ..get_header(1)
+        };
+        assert!(matches!(
+            cache.verify_pow(&header),
+            Err(PowError::InvalidMixHash { .. })
+        ));
+
+        assert_eq!(
+            cache.verify_pow(&get_header(1_000_001)),
+            Err(PowError::EpochMismatch {
+                cache_epoch: 0,
+                header_epoch: 33,
+            })
+        );
+    }
+}
@@ -0,0 +1,295 @@
+use alloy::{consensus::Header, primitives::B256};
+use ethportal_api::types::execution::header_with_proof::{
+    BlockHeaderProof, BlockProofHistoricalRoots, BlockProofHistoricalSummaries, HeaderWithProof,
+    ProofError,
+};
+
+use crate::header_validator::HeaderValidator;
+
+/// Reconstructs the beacon block root from the proof that the execution block header is part of
+/// the beacon block.
+///
+/// This only covers the execution block half of the proof, and is useful for debugging proofs
+/// that fail verification, by comparing the result with the claimed `beacon_block_root`.
+pub trait ReconstructBeaconBlockRoot {
+    fn reconstruct_beacon_block_root(&self, header: &Header) -> Result<B256, ProofError>;
+}
+
+impl ReconstructBeaconBlockRoot for BlockProofHistoricalRoots {
+    fn reconstruct_beacon_block_root(&self, header: &Header) -> Result<B256, ProofError> {
+        HeaderValidator::reconstruct_beacon_block_root(
+            header.hash_slow(),
+            header.timestamp,
+            self.execution_block_proof.iter().copied(),
+        )
+    }
+}
+
+impl ReconstructBeaconBlockRoot for BlockProofHistoricalSummaries {
+    fn reconstruct_beacon_block_root(&self, header: &Header) -> Result<B256, ProofError> {
+        HeaderValidator::reconstruct_beacon_block_root(
+            header.hash_slow(),
+            header.timestamp,
+            self.execution_block_proof.iter().copied(),
+        )
+    }
+}
+
+/// Verifies only the proof that the execution block header is part of a trusted beacon block.
+///
+/// This is meant for nodes that already trust the `beacon_block_root` (e.g. from their own
+/// consensus client), so they don't need the rest of the proof up to the historical anchors.
+pub trait VerifyExecutionOnly {
+    fn verify_execution_only(&self, trusted_beacon_block_root: B256) -> Result<(), ProofError>;
+}
+
+impl VerifyExecutionOnly for HeaderWithProof {
+    fn verify_execution_only(&self, trusted_beacon_block_root: B256) -> Result<(), ProofError> {
+        let beacon_block_root = match &self.proof {
+            BlockHeaderProof::HistoricalHashes(_) => {
+                return Err(ProofError::UnexpectedProofType {
+                    block_number: self.header.number,
+                })
+            }
+            BlockHeaderProof::HistoricalRoots(proof) => {
+                proof.reconstruct_beacon_block_root(&self.header)?
+            }
+            BlockHeaderProof::HistoricalSummaries(proof) => {
+                proof.reconstruct_beacon_block_root(&self.header)?
+            }
+        };
+        if beacon_block_root != trusted_beacon_block_root {
+            return Err(ProofError::InvalidBeaconBlockProof);
+        }
+        Ok(())
+    }
+}
+
+/// Verifies the proof that the execution block header is part of the beacon block, against the
+/// beacon block root resolved by slot with an oracle (e.g. an external beacon node).
+///
+/// This doesn't need the historical anchors, as the resolved beacon block root is trusted.
+pub trait VerifyWithOracle {
+    fn verify_with_oracle(&self, oracle: &impl Fn(u64) -> Option<B256>) -> Result<(), ProofError>;
+}
+
+impl VerifyWithOracle for HeaderWithProof {
+    fn verify_with_oracle(&self, oracle: &impl Fn(u64) -> Option<B256>) -> Result<(), ProofError> {
+        let slot = match &self.proof {
+            BlockHeaderProof::HistoricalHashes(_) => {
+                return Err(ProofError::UnexpectedProofType {
+                    block_number: self.header.number,
+                })
+            }
+            BlockHeaderProof::HistoricalRoots(proof) => proof.slot,
+            BlockHeaderProof::HistoricalSummaries(proof) => proof.slot,
+        };
+        let beacon_block_root = oracle(slot).ok_or(ProofError::UnknownBeaconBlockRoot { slot })?;
+        self.verify_execution_only(beacon_block_root)
+    }
+}
+
+/// Checks the internal integrity of a header with proof, i.e. that the proof that the execution
+/// block header is part of the beacon block roots to the claimed `beacon_block_root`.
+///
+/// This doesn't need any historical anchors, so it's a cheap pre-filter before the full
+/// verification. Pre-merge proofs don't claim any intermediate root, so there is nothing to check.
+pub trait CheckInternalIntegrity {
+    fn check_internal_integrity(&self) -> Result<(), ProofError>;
+}
+
+impl CheckInternalIntegrity for HeaderWithProof {
+    fn check_internal_integrity(&self) -> Result<(), ProofError> {
+        match &self.proof {
+            BlockHeaderProof::HistoricalHashes(_) => Ok(()),
+            BlockHeaderProof::HistoricalRoots(proof) => {
+                self.verify_execution_only(proof.beacon_block_root)
+            }
+            BlockHeaderProof::HistoricalSummaries(proof) => {
+                self.verify_execution_only(proof.beacon_block_root)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::collections::HashMap;
+
+    use ethportal_api::consensus::historical_summaries::HistoricalSummaries;
+
+    use super::*;
+    use crate::{
+        constants::SHANGHAI_BLOCK_NUMBER, summaries_verifier::SummariesVerifier,
+        test_utils::random_historical_summaries_proof,
+    };
+
+    #[test]
+    fn reconstruct_beacon_block_root() {
+        let header = Header {
+            number: SHANGHAI_BLOCK_NUMBER,
+            ..Default::default()
+        };
+        let (proof, _) = random_historical_summaries_proof(&header, 6_209_538);
+
+        assert_eq!(
+            proof.reconstruct_beacon_block_root(&header),
+            Ok(proof.beacon_block_root)
+        );
+
+        let historical_roots_proof = BlockProofHistoricalRoots {
+            beacon_block_proof: vec![B256::random(); 14].into(),
+            beacon_block_root: proof.beacon_block_root,
+            execution_block_proof: proof.execution_block_proof.to_vec().into(),
+            slot: proof.slot,
+        };
+        assert_eq!(
+            historical_roots_proof.reconstruct_beacon_block_root(&header),
+            Ok(proof.beacon_block_root)
+        );
+    }
+
+    #[test]
+    fn reconstruct_beacon_block_root_for_other_header() {
+        let header = Header {
+            number: SHANGHAI_BLOCK_NUMBER,
+            ..Default::default()
+        };
+        let (proof, _) = random_historical_summaries_proof(&header, 6_209_538);
+        let other_header = Header {
+            number: SHANGHAI_BLOCK_NUMBER + 1,
+            ..Default::default()
+        };
+
+        assert_ne!(
+            proof.reconstruct_beacon_block_root(&other_header).unwrap(),
+            proof.beacon_block_root
+        );
+    }
+
+    #[test]
+    fn reconstruct_beacon_block_root_with_invalid_proof_length() {
+        let header = Header::default();
+        let (mut proof, _) = random_historical_summaries_proof(&header, 6_209_538);
+        proof.execution_block_proof = vec![B256::random(); 12].into();
+
+        assert_eq!(
+            proof.reconstruct_beacon_block_root(&header),
+            Err(ProofError::InvalidExecutionBlockProofLength { length: 12 })
+        );
+    }
+
+    #[test]
+    fn verify_execution_only() {
+        let header = Header {
+            number: SHANGHAI_BLOCK_NUMBER,
+            ..Default::default()
+        };
+        let (proof, _) = random_historical_summaries_proof(&header, 6_209_538);
+        let beacon_block_root = proof.beacon_block_root;
+        let header_with_proof = HeaderWithProof {
+            header,
+            proof: BlockHeaderProof::HistoricalSummaries(proof),
+        };
+
+        assert_eq!(
+            header_with_proof.verify_execution_only(beacon_block_root),
+            Ok(())
+        );
+        assert_eq!(
+            header_with_proof.verify_execution_only(B256::random()),
+            Err(ProofError::InvalidBeaconBlockProof)
+        );
+    }
+
+    #[test]
+    fn verify_execution_only_pre_merge() {
+        let header_with_proof = HeaderWithProof {
+            header: Header::default(),
+            proof: BlockHeaderProof::HistoricalHashes(vec![B256::random(); 15].into()),
+        };
+
+        assert_eq!(
+            header_with_proof.verify_execution_only(B256::random()),
+            Err(ProofError::UnexpectedProofType { block_number: 0 })
+        );
+    }
+
+    #[test]
+    fn verify_with_oracle() {
+        let header = Header {
+            number: SHANGHAI_BLOCK_NUMBER,
+            ..Default::default()
+        };
+        let (proof, _) = random_historical_summaries_proof(&header, 6_209_538);
+        let beacon_block_roots = HashMap::from([
+            (proof.slot, proof.beacon_block_root),
+            (6209539, B256::random()),
+        ]);
+        let oracle = |slot| beacon_block_roots.get(&slot).copied();
+
+        let mut header_with_proof = HeaderWithProof {
+            header,
+            proof: BlockHeaderProof::HistoricalSummaries(proof.clone()),
+        };
+        assert_eq!(header_with_proof.verify_with_oracle(&oracle), Ok(()));
+
+        // The oracle resolves another root for the slot of the proof
+        let mut other_slot_proof = proof.clone();
+        other_slot_proof.slot = 6209539;
+        header_with_proof.proof = BlockHeaderProof::HistoricalSummaries(other_slot_proof);
+        assert_eq!(
+            header_with_proof.verify_with_oracle(&oracle),
+            Err(ProofError::InvalidBeaconBlockProof)
+        );
+
+        let mut unknown_slot_proof = proof;
+        unknown_slot_proof.slot = 6209540;
+        header_with_proof.proof = BlockHeaderProof::HistoricalSummaries(unknown_slot_proof);
+        assert_eq!(
+            header_with_proof.verify_with_oracle(&oracle),
+            Err(ProofError::UnknownBeaconBlockRoot { slot: 6209540 })
+        );
+    }
+
+    #[test]
+    fn check_internal_integrity_without_anchors() {
+        let header = Header {
+            number: SHANGHAI_BLOCK_NUMBER,
+            ..Default::default()
+        };
+        let (proof, _) = random_historical_summaries_proof(&header, 6_209_538);
+        let header_with_proof = HeaderWithProof {
+            header: header.clone(),
+            proof: BlockHeaderProof::HistoricalSummaries(proof.clone()),
+        };
+
+        assert_eq!(header_with_proof.check_internal_integrity(), Ok(()));
+        // The full verification needs the historical summaries of the period of the slot
+        let historical_summaries = HistoricalSummaries::default();
+        assert!(matches!(
+            SummariesVerifier::new(&historical_summaries).verify(&proof, &header),
+            Err(ProofError::NotYetFinalized { .. })
+        ));
+    }
+
+    #[test]
+    fn check_internal_integrity_with_other_beacon_block_root() {
+        let header = Header {
+            number: SHANGHAI_BLOCK_NUMBER,
+            ..Default::default()
+        };
+        let (mut proof, _) = random_historical_summaries_proof(&header, 6_209_538);
+        proof.beacon_block_root = B256::random();
+        let header_with_proof = HeaderWithProof {
+            header,
+            proof: BlockHeaderProof::HistoricalSummaries(proof),
+        };
+
+        assert_eq!(
+            header_with_proof.check_internal_integrity(),
+            Err(ProofError::InvalidBeaconBlockProof)
+        );
+    }
+}
@@ -0,0 +1,373 @@
+use alloy::{
+    consensus::{Header, Transaction, EMPTY_OMMER_ROOT_HASH},
+    primitives::U256,
+};
+use ethportal_api::types::execution::{block_body::BlockBody, header::difficulty_bomb_delay};
+use thiserror::Error;
+
+use crate::constants::{
+    BYZANTIUM_BLOCK_NUMBER, HOMESTEAD_BLOCK_NUMBER, LONDON_BLOCK_NUMBER, MERGE_BLOCK_NUMBER,
+    PRAGUE_BLOCK_NUMBER,
+};
+
+/// Base gas cost of every transaction.
+const TX_BASE_COST: u64 = 21_000;
+
+/// Gas cost per calldata token of the calldata floor, introduced with EIP-7623.
+const TOTAL_COST_FLOOR_PER_TOKEN: u64 = 10;
+
+/// Bound divisor of the gas limit, which limits its change between consecutive blocks.
+const GAS_LIMIT_BOUND_DIVISOR: u64 = 1024;
+
+/// Minimum gas limit of a block.
+const MIN_GAS_LIMIT: u64 = 5_000;
+
+/// Multiplier of the gas target that gives the gas limit, introduced with EIP-1559.
+const ELASTICITY_MULTIPLIER: u64 = 2;
+
+/// Bound divisor of the difficulty, which limits its change between consecutive blocks.
+const DIFFICULTY_BOUND_DIVISOR: u64 = 2048;
+
+/// Minimum difficulty of a block.
+const MIN_DIFFICULTY: u64 = 131_072;
+
+/// Number of blocks in a period of the difficulty bomb, which doubles its value every period.
+const EXP_DIFFICULTY_PERIOD: u64 = 100_000;
+
+/// Block time below which the Frontier difficulty increases.
+const FRONTIER_DURATION_LIMIT: u64 = 13;
+
+/// An error validating the header against the consensus rules.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum HeaderValidationError {
+    /// The header's `gas_used` is lower than the calldata floor of the block's transactions.
+    #[error("gas used {gas_used} is lower than the calldata floor {calldata_floor}")]
+    GasUsedBelowCalldataFloor { gas_used: u64, calldata_floor: u64 },
+    /// The header's timestamp is earlier than the parent's timestamp plus the block period.
+    #[cfg(feature = "poa")]
+    #[error("timestamp {timestamp} is earlier than the minimum timestamp {min_timestamp}")]
+    TimestampBeforeBlockPeriod { timestamp: u64, min_timestamp: u64 },
+    /// The header's gas limit changed too much from the parent's gas limit.
+    #[error("gas limit {gas_limit} changed too much from the parent gas limit {parent_gas_limit}")]
+    GasLimitChangeTooLarge {
+        gas_limit: u64,
+        parent_gas_limit: u64,
+    },
+    /// The header's gas limit is lower than the minimum gas limit.
+    #[error("gas limit {gas_limit} is lower than the minimum gas limit")]
+    GasLimitBelowMinimum { gas_limit: u64 },
+}
+
+/// Validates that the header's `gas_used` is at least the calldata floor (EIP-7623) of the
+/// block's transactions.
+///
+/// The calldata floor applies from Prague onwards, so pre-Prague headers are always valid.
+pub fn validate_prague_gas_invariants(
+    header: &Header,
+    body: &BlockBody,
+) -> Result<(), HeaderValidationError> {
+    if header.number < PRAGUE_BLOCK_NUMBER {
+        return Ok(());
+    }
+
+    let calldata_floor = body
+        .transactions
+        .iter()
+        .map(|tx| calldata_floor(tx.input()))
+        .sum();
+    if header.gas_used < calldata_floor {
+        return Err(HeaderValidationError::GasUsedBelowCalldataFloor {
+            gas_used: header.gas_used,
+            calldata_floor,
+        });
+    }
+    Ok(())
+}
+
+/// Validates that the child header's gas limit changed by less than 1/1024 of the parent's gas
+/// limit, and that it's above the minimum gas limit.
+///
+/// The gas limit of the London block is compared with the parent's gas limit multiplied by the
+/// elasticity multiplier (EIP-1559), as the gas limit was doubled at the fork.
+pub fn validate_gas_limit_bounds(
+    parent: &Header,
+    child: &Header,
+) -> Result<(), HeaderValidationError> {
+    let parent_gas_limit = match child.number == LONDON_BLOCK_NUMBER {
+        true => parent.gas_limit.saturating_mul(ELASTICITY_MULTIPLIER),
+        false => parent.gas_limit,
+    };
+    if child.gas_limit.abs_diff(parent_gas_limit) >= parent_gas_limit / GAS_LIMIT_BOUND_DIVISOR {
+        return Err(HeaderValidationError::GasLimitChangeTooLarge {
+            gas_limit: child.gas_limit,
+            parent_gas_limit,
+        });
+    }
+    if child.gas_limit < MIN_GAS_LIMIT {
+        return Err(HeaderValidationError::GasLimitBelowMinimum {
+            gas_limit: child.gas_limit,
+        });
+    }
+    Ok(())
+}
+
+/// Returns the difficulty of the block with the given number and timestamp, as computed from its
+/// parent header by the Frontier, Homestead (EIP-2) or Byzantium (EIP-100) difficulty formula.
+///
+/// The difficulty bomb is added on top of the adjusted difficulty, with the delays of the
+/// difficulty bomb forks applied. Post-merge blocks have a difficulty of zero.
+pub fn expected_difficulty(parent: &Header, child_timestamp: u64, block_number: u64) -> U256 {
+    if block_number >= MERGE_BLOCK_NUMBER {
+        return U256::ZERO;
+    }
+
+    let parent_difficulty = parent.difficulty;
+    let adjustment = parent_difficulty / U256::from(DIFFICULTY_BOUND_DIVISOR);
+    let block_time = child_timestamp.saturating_sub(parent.timestamp);
+    let difficulty = if block_number < HOMESTEAD_BLOCK_NUMBER {
+        match block_time < FRONTIER_DURATION_LIMIT {
+            true => parent_difficulty + adjustment,
+            false => parent_difficulty.saturating_sub(adjustment),
+        }
+    } else {
+        let (base, divisor) = match block_number < BYZANTIUM_BLOCK_NUMBER {
+            true => (1, 10),
+            false => match parent.ommers_hash == EMPTY_OMMER_ROOT_HASH {
+                true => (1, 9),
+                false => (2, 9),
+            },
+        };
+        let factor = (base - (block_time / divisor) as i64).max(-99);
+        match factor.is_negative() {
+            true => {
+                parent_difficulty.saturating_sub(adjustment * U256::from(factor.unsigned_abs()))
+            }
+            false => parent_difficulty + adjustment * U256::from(factor),
+        }
+    };
+    let difficulty = difficulty.max(U256::from(MIN_DIFFICULTY));
+
+    let bomb_delay = difficulty_bomb_delay(&Header {
+        number: block_number,
+        ..Default::default()
+    })
+    .unwrap_or_default();
+    let period = block_number.saturating_sub(bomb_delay) / EXP_DIFFICULTY_PERIOD;
+    match period > 1 {
+        true => difficulty + (U256::from(1) << (period - 2)),
+        false => difficulty,
+    }
+}
+
+/// Validates that the child header respects the block period of a Clique/PoA chain, i.e. that it
+/// isn't produced earlier than `period_secs` after its parent.
+#[cfg(feature = "poa")]
+pub fn validate_poa_period(
+    parent: &Header,
+    child: &Header,
+    period_secs: u64,
+) -> Result<(), HeaderValidationError> {
+    let min_timestamp = parent.timestamp.saturating_add(period_secs);
+    if child.timestamp < min_timestamp {
+        return Err(HeaderValidationError::TimestampBeforeBlockPeriod {
+            timestamp: child.timestamp,
+            min_timestamp,
+        });
+    }
+    Ok(())
+}
+
+/// Returns the minimum gas used by a transaction with the given calldata, as defined by EIP-7623.
+fn calldata_floor(calldata: &[u8]) -> u64 {
+    let zero_bytes = calldata.iter().filter(|byte| **byte == 0).count() as u64;
+    let non_zero_bytes = calldata.len() as u64 - zero_bytes;
+    let tokens = zero_bytes + 4 * non_zero_bytes;
+    TX_BASE_COST + TOTAL_COST_FLOOR_PER_TOKEN * tokens
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use alloy::{consensus::BlockBody as AlloyBlockBody, primitives::B256};
+    use rstest::rstest;
+    use serde_json::Value;
+    use trin_utils::test_assets::read_test_assets_file;
+
+    use super::*;
+
+    /// Reads the header and body of the block 19433902.
+    fn read_block_19433902() -> (Header, BlockBody) {
+        let file = read_test_assets_file("mainnet/block_19433902_value.json").unwrap();
+        let json: Value = serde_json::from_str(&file).unwrap();
+        let block = &json["result"];
+        let header: Header = serde_json::from_value(block.clone()).unwrap();
+        let body = BlockBody(AlloyBlockBody {
+            transactions: serde_json::from_value(block["transactions"].clone()).unwrap(),
+            ommers: vec![],
+            withdrawals: Some(serde_json::from_value(block["withdrawals"].clone()).unwrap()),
+        });
+        (header, body)
+    }
+
+    #[test]
+    fn calldata_floor_per_token() {
+        assert_eq!(calldata_floor(&[]), 21_000);
+        assert_eq!(calldata_floor(&[0, 0]), 21_020);
+        assert_eq!(calldata_floor(&[0, 1, 2]), 21_090);
+    }
+
+    #[test]
+    fn prague_gas_invariants() {
+        // The block is pre-Prague, so it's moved to Prague in order to apply the calldata floor
+        let (mut header, body) = read_block_19433902();
+        header.number = PRAGUE_BLOCK_NUMBER;
+        validate_prague_gas_invariants(&header, &body).unwrap();
+
+        header.gas_used = 21_000;
+        assert!(matches!(
+            validate_prague_gas_invariants(&header, &body),
+            Err(HeaderValidationError::GasUsedBelowCalldataFloor {
+                gas_used: 21_000,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn prague_gas_invariants_pre_prague() {
+        let (mut header, body) = read_block_19433902();
+        header.gas_used = 0;
+        validate_prague_gas_invariants(&header, &body).unwrap();
+    }
+
+    #[test]
+    fn gas_limit_bounds() {
+        let file = std::fs::read_to_string("./src/assets/header_rlps.json").unwrap();
+        let json: Value = serde_json::from_str(&file).unwrap();
+        let header = |number: u64| -> Header {
+            let raw_header =
+                alloy::hex::decode(json[number.to_string()].as_str().unwrap()).unwrap();
+            alloy::rlp::Decodable::decode(&mut raw_header.as_slice()).unwrap()
+        };
+        let parent = header(1_000_001);
+        let child = header(1_000_002);
+        validate_gas_limit_bounds(&parent, &child).unwrap();
+
+        let child = Header {
+            gas_limit: parent.gas_limit + parent.gas_limit / 1024,
+            ..child
+        };
+        assert_eq!(
+            validate_gas_limit_bounds(&parent, &child),
+            Err(HeaderValidationError::GasLimitChangeTooLarge {
+                gas_limit: child.gas_limit,
+                parent_gas_limit: parent.gas_limit,
+            })
+        );
+
+        let parent = Header {
+            gas_limit: 5_000,
+            ..Default::default()
+        };
+        let child = Header {
+            number: 1,
+            gas_limit: 4_999,
+            ..Default::default()
+        };
+        assert_eq!(
+            validate_gas_limit_bounds(&parent, &child),
+            Err(HeaderValidationError::GasLimitBelowMinimum { gas_limit: 4_999 })
+        );
+    }
+
+    #[test]
+    fn gas_limit_bounds_at_london() {
+        let parent = Header {
+            number: LONDON_BLOCK_NUMBER - 1,
+            gas_limit: 15_000_000,
+            ..Default::default()
+        };
+        let child = |number| Header {
+            number,
+            gas_limit: 30_000_000,
+            ..Default::default()
+        };
+
+        validate_gas_limit_bounds(&parent, &child(LONDON_BLOCK_NUMBER)).unwrap();
+        assert!(validate_gas_limit_bounds(&parent, &child(LONDON_BLOCK_NUMBER + 1)).is_err());
+    }
+
+    #[rstest]
+    #[case::frontier(1)]
+    #[case::frontier_decreasing_with_bomb(1_000_001)]
+    #[case::frontier_increasing_with_bomb(1_000_002)]
+    #[case::frontier_with_bomb(1_000_005)]
+    fn expected_difficulty_of_consecutive_blocks(#[case] parent_number: u64) {
+        let file = std::fs::read_to_string("./src/assets/header_rlps.json").unwrap();
+        let json: Value = serde_json::from_str(&file).unwrap();
+        let header = |number: u64| -> Header {
+            let raw_header =
+                alloy::hex::decode(json[number.to_string()].as_str().unwrap()).unwrap();
+            alloy::rlp::Decodable::decode(&mut raw_header.as_slice()).unwrap()
+        };
+        let parent = header(parent_number);
+        let child = header(parent_number + 1);
+        assert_eq!(
+            expected_difficulty(&parent, child.timestamp, child.number),
+            child.difficulty
+        );
+    }
+
+    #[test]
+    fn expected_difficulty_of_terminal_block() {
+        // The parent of the terminal PoW block, with only the fields used by the formula.
+        let parent = Header {
+            number: 15_537_392,
+            difficulty: U256::from(11_049_842_297_455_506_u64),
+            timestamp: 1_663_224_156,
+            ommers_hash: EMPTY_OMMER_ROOT_HASH,
+            ..Default::default()
+        };
+        assert_eq!(
+            expected_difficulty(&parent, 1_663_224_162, 15_537_393),
+            U256::from(11_055_787_484_078_698_u64)
+        );
+
+        let parent = Header {
+            ommers_hash: B256::random(),
+            ..parent
+        };
+        assert!(
+            expected_difficulty(&parent, 1_663_224_162, 15_537_393)
+                > U256::from(11_055_787_484_078_698_u64)
+        );
+        assert_eq!(
+            expected_difficulty(&parent, 1_663_224_168, MERGE_BLOCK_NUMBER),
+            U256::ZERO
+        );
+    }
+
+    #[cfg(feature = "poa")]
+    #[test]
+    fn poa_period() {
+        let parent = Header {
+            timestamp: 1_000,
+            ..Default::default()
+        };
+        let child = |timestamp| Header {
+            number: 1,
+            timestamp,
+            ..Default::default()
+        };
+
+        validate_poa_period(&parent, &child(1_015), 15).unwrap();
+        validate_poa_period(&parent, &child(1_020), 15).unwrap();
+        assert_eq!(
+            validate_poa_period(&parent, &child(1_014), 15),
+            Err(HeaderValidationError::TimestampBeforeBlockPeriod {
+                timestamp: 1_014,
+                min_timestamp: 1_015,
+            })
+        );
+    }
+}
@@ -1,21 +1,41 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use alloy::{consensus::Header, primitives::B256};
 use anyhow::anyhow;
 use ethportal_api::{
     consensus::historical_summaries::HistoricalSummaries,
-    types::execution::header_with_proof::{
-        BlockHeaderProof, BlockProofHistoricalRoots, BlockProofHistoricalSummaries, HeaderWithProof,
+    types::execution::{
+        header::HeaderFork,
+        header_with_proof::{
+            BlockHeaderProof, BlockProofHistoricalHashesAccumulator, BlockProofHistoricalRoots,
+            BlockProofHistoricalSummaries, HeaderWithProof, ProofError,
+        },
     },
 };
 
 use crate::{
     accumulator::PreMergeAccumulator,
     constants::{
-        CAPELLA_FORK_EPOCH, EPOCH_SIZE, MERGE_BLOCK_NUMBER, SHANGHAI_BLOCK_NUMBER, SLOTS_PER_EPOCH,
+        CAPELLA_FORK_EPOCH, EPOCH_SIZE, EXECUTION_BLOCK_PROOF_DEPTH,
+        EXECUTION_BLOCK_PROOF_DEPTH_DENEB, EXECUTION_BLOCK_PROOF_GEN_INDEX,
+        EXECUTION_BLOCK_PROOF_GEN_INDEX_DENEB, HISTORICAL_HASHES_PROOF_DEPTH,
+        HISTORICAL_ROOTS_PROOF_DEPTH, HISTORICAL_SUMMARIES_PROOF_DEPTH, MERGE_BLOCK_NUMBER,
+        SHANGHAI_BLOCK_NUMBER, SLOTS_PER_EPOCH,
     },
     historical_roots_acc::HistoricalRootsAccumulator,
-    merkle::proof::verify_merkle_proof,
+    merkle::proof::{merkle_root_from_branch_iter, verify_merkle_proof},
+    verification_record::{ProofKind, VerificationRecord},
 };
 
+/// How a batch of headers is verified by [HeaderValidator::verify_batch_with].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatchMode {
+    /// Stop at the first invalid header, without verifying the rest of the batch.
+    FailFast,
+    /// Verify every header of the batch.
+    CollectAll,
+}
+
 /// HeaderValidator is responsible for validating pre-merge and post-merge headers with their
 /// respective proofs.
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
@@ -40,33 +60,11 @@ impl HeaderValidator {
     pub fn validate_header_with_proof(&self, hwp: &HeaderWithProof) -> anyhow::Result<()> {
         match &hwp.proof {
             BlockHeaderProof::HistoricalHashes(proof) => {
-                if hwp.header.number > MERGE_BLOCK_NUMBER {
-                    return Err(anyhow!("Invalid proof type found for post-merge header."));
-                }
-                // Look up historical epoch hash for header from pre-merge accumulator
-                let gen_index = calculate_generalized_index(&hwp.header);
-                let epoch_index =
-                    self.pre_merge_acc.get_epoch_index_of_header(&hwp.header) as usize;
-                let epoch_hash = self.pre_merge_acc.historical_epochs[epoch_index];
-
-                match verify_merkle_proof(
-                    hwp.header.hash_slow(),
-                    proof,
-                    15,
-                    gen_index as usize,
-                    epoch_hash,
-                ) {
-                    true => Ok(()),
-                    false => Err(anyhow!(
-                        "Merkle proof validation failed for pre-merge header"
-                    )),
-                }
+                self.verify_pre_merge_header(&hwp.header, proof)?
+            }
+            BlockHeaderProof::HistoricalRoots(proof) => {
+                self.verify_post_merge_pre_capella_header(&hwp.header, proof)?
             }
-            BlockHeaderProof::HistoricalRoots(proof) => self.verify_post_merge_pre_capella_header(
-                hwp.header.number,
-                hwp.header.hash_slow(),
-                proof,
-            ),
             BlockHeaderProof::HistoricalSummaries(_) => {
                 if hwp.header.number < SHANGHAI_BLOCK_NUMBER {
                     return Err(anyhow!(
@@ -74,32 +72,154 @@ impl HeaderValidator {
                     ));
                 }
                 // TODO: Validation for post-Capella headers is not implemented
-                Ok(())
             }
         }
+        Ok(())
+    }
+
+    /// Verifies the chain of proofs of the header with proof, using the `historical_summaries` to
+    /// verify post-Capella headers.
+    pub fn verify_header_with_proof(
+        &self,
+        hwp: &HeaderWithProof,
+        historical_summaries: &HistoricalSummaries,
+    ) -> Result<(), ProofError> {
+        match &hwp.proof {
+            BlockHeaderProof::HistoricalHashes(proof) => {
+                self.verify_pre_merge_header(&hwp.header, proof)
+            }
+            BlockHeaderProof::HistoricalRoots(proof) => {
+                self.verify_post_merge_pre_capella_header(&hwp.header, proof)
+            }
+            BlockHeaderProof::HistoricalSummaries(proof) => {
+                self.verify_post_capella_header(&hwp.header, proof, historical_summaries)
+            }
+        }
+    }
+
+    /// Verifies the header with proof like [Self::verify_header_with_proof], returning the number
+    /// of the verified header.
+    ///
+    /// This is useful for callers that log which block was verified, as the success path
+    /// carries the identity of the header.
+    pub fn verify_and_identify(
+        &self,
+        hwp: &HeaderWithProof,
+        historical_summaries: &HistoricalSummaries,
+    ) -> Result<u64, ProofError> {
+        self.verify_header_with_proof(hwp, historical_summaries)?;
+        Ok(hwp.header.number)
+    }
+
+    /// Validates a batch of headers with proofs, using the `historical_summaries` to validate
+    /// post-Capella headers.
+    ///
+    /// Returns the result of each validated header, in order. With [BatchMode::FailFast], the
+    /// last result is the first error, and the headers after it are not validated.
+    pub fn verify_batch_with(
+        &self,
+        hwps: &[HeaderWithProof],
+        historical_summaries: &HistoricalSummaries,
+        mode: BatchMode,
+    ) -> Vec<Result<(), ProofError>> {
+        let mut results = Vec::with_capacity(hwps.len());
+        for hwp in hwps {
+            let result = self.verify_header_with_proof(hwp, historical_summaries);
+            let is_err = result.is_err();
+            results.push(result);
+            if is_err && mode == BatchMode::FailFast {
+                break;
+            }
+        }
+        results
+    }
+
+    /// Validates the header with proof like [Self::verify_batch_with], and returns the record of
+    /// the verification for audit logs, instead of the result.
+    pub fn verify_and_record(
+        &self,
+        hwp: &HeaderWithProof,
+        historical_summaries: &HistoricalSummaries,
+    ) -> VerificationRecord {
+        let anchor_root = match &hwp.proof {
+            BlockHeaderProof::HistoricalHashes(_) => {
+                let epoch_index = self.pre_merge_acc.get_epoch_index_of_header(&hwp.header);
+                self.pre_merge_acc
+                    .historical_epochs
+                    .get(epoch_index as usize)
+                    .copied()
+            }
+            BlockHeaderProof::HistoricalRoots(proof) => self
+                .historical_roots_acc
+                .historical_roots
+                .get((proof.slot / EPOCH_SIZE) as usize)
+                .copied(),
+            BlockHeaderProof::HistoricalSummaries(proof) => {
+                // A pre-Capella slot has no historical summaries period, so no anchor
+                Self::historical_summaries_period(proof.slot)
+                    .ok()
+                    .and_then(|period| historical_summaries.get(period as usize))
+                    .map(|historical_summary| historical_summary.block_summary_root)
+            }
+        };
+        let result = self.verify_header_with_proof(hwp, historical_summaries);
+        let verified_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+        VerificationRecord {
+            content_id: hwp.canonical_content_id(),
+            proof_kind: ProofKind::from(&hwp.proof),
+            anchor_root: anchor_root.unwrap_or_default(),
+            verified_at,
+            outcome: result.is_ok(),
+        }
+    }
+
+    /// Verify that the pre-merge header is included in the pre-merge accumulator.
+    fn verify_pre_merge_header(
+        &self,
+        header: &Header,
+        proof: &BlockProofHistoricalHashesAccumulator,
+    ) -> Result<(), ProofError> {
+        // The pre-merge accumulator ends with the terminal PoW block, right before the merge block
+        if header.number >= MERGE_BLOCK_NUMBER {
+            return Err(ProofError::UnexpectedProofType {
+                block_number: header.number,
+            });
+        }
+        // Look up historical epoch hash for header from pre-merge accumulator
+        let gen_index = calculate_generalized_index(header);
+        let epoch_index = self.pre_merge_acc.get_epoch_index_of_header(header) as usize;
+        let epoch_hash = self.pre_merge_acc.historical_epochs[epoch_index];
+
+        if !verify_merkle_proof(
+            header.hash_slow(),
+            proof,
+            HISTORICAL_HASHES_PROOF_DEPTH,
+            gen_index as usize,
+            epoch_hash,
+        ) {
+            return Err(ProofError::InvalidHistoricalHashesProof);
+        }
+
+        Ok(())
     }
 
     /// A method to verify the chain of proofs for post-merge/pre-Capella execution headers.
     fn verify_post_merge_pre_capella_header(
         &self,
-        block_number: u64,
-        header_hash: B256,
+        header: &Header,
         proof: &BlockProofHistoricalRoots,
-    ) -> anyhow::Result<()> {
-        if block_number <= MERGE_BLOCK_NUMBER {
-            return Err(anyhow!(
-                "Invalid HistoricalRootsBlockProof found for pre-merge header."
-            ));
-        }
-        if block_number >= SHANGHAI_BLOCK_NUMBER {
-            return Err(anyhow!(
-                "Invalid HistoricalRootsBlockProof found for post-Shanghai header."
-            ));
+    ) -> Result<(), ProofError> {
+        if !(MERGE_BLOCK_NUMBER..SHANGHAI_BLOCK_NUMBER).contains(&header.number) {
+            return Err(ProofError::UnexpectedProofType {
+                block_number: header.number,
+            });
         }
 
         // Verify the chain of proofs for post-merge/pre-capella block header
         Self::verify_beacon_block_proof(
-            header_hash,
+            header,
             &proof.execution_block_proof,
             proof.beacon_block_root,
         )?;
@@ -107,100 +227,175 @@ impl HeaderValidator {
         let block_root_index = proof.slot % EPOCH_SIZE;
         let gen_index = 2 * EPOCH_SIZE + block_root_index;
         let historical_root_index = proof.slot / EPOCH_SIZE;
-        let historical_root =
-            self.historical_roots_acc.historical_roots[historical_root_index as usize];
+        let historical_root = self
+            .historical_roots_acc
+            .historical_roots
+            .get(historical_root_index as usize)
+            .ok_or(ProofError::MissingHistoricalRoot {
+                index: historical_root_index,
+            })?;
 
         if !verify_merkle_proof(
             proof.beacon_block_root,
             &proof.beacon_block_proof,
-            14,
+            HISTORICAL_ROOTS_PROOF_DEPTH,
             gen_index as usize,
-            historical_root,
+            *historical_root,
         ) {
-            return Err(anyhow!(
-                "Merkle proof validation failed for HistoricalRootsProof"
-            ));
+            return Err(ProofError::InvalidHistoricalRootsProof);
         }
 
         Ok(())
     }
 
     /// A method to verify the chain of proofs for post-Capella execution headers.
-    #[allow(dead_code)] // TODO: Remove this when used
-    fn verify_post_capella_header(
+    pub(crate) fn verify_post_capella_header(
         &self,
-        block_number: u64,
-        header_hash: B256,
+        header: &Header,
         proof: &BlockProofHistoricalSummaries,
-        historical_summaries: HistoricalSummaries,
-    ) -> anyhow::Result<()> {
-        if block_number < SHANGHAI_BLOCK_NUMBER {
-            return Err(anyhow!(
-                "Invalid HistoricalSummariesBlockProof found for pre-Shanghai header."
-            ));
+        historical_summaries: &HistoricalSummaries,
+    ) -> Result<(), ProofError> {
+        if header.number < SHANGHAI_BLOCK_NUMBER {
+            return Err(ProofError::UnexpectedProofType {
+                block_number: header.number,
+            });
         }
 
-        // Verify the chain of proofs for post-merge/pre-capella block header
+        // Verify the chain of proofs for post-capella block header
         Self::verify_beacon_block_proof(
-            header_hash,
+            header,
             &proof.execution_block_proof,
             proof.beacon_block_root,
         )?;
+        Self::verify_historical_summaries_beacon_block_proof(proof, historical_summaries)
+    }
 
+    /// Verify that the beacon block is included in the `historical_summaries`.
+    ///
+    /// Proofs of beacon blocks that are more recent than the latest historical summaries period
+    /// fail with [ProofError::NotYetFinalized].
+    pub(crate) fn verify_historical_summaries_beacon_block_proof(
+        proof: &BlockProofHistoricalSummaries,
+        historical_summaries: &HistoricalSummaries,
+    ) -> Result<(), ProofError> {
         let block_root_index = proof.slot % EPOCH_SIZE;
         let gen_index = EPOCH_SIZE + block_root_index;
-        let historical_summary_index =
-            (proof.slot - CAPELLA_FORK_EPOCH * SLOTS_PER_EPOCH) / EPOCH_SIZE;
-        let historical_summary =
-            historical_summaries[historical_summary_index as usize].block_summary_root;
+        let historical_summary_index = Self::historical_summaries_period(proof.slot)?;
+        let historical_summary = historical_summaries
+            .get(historical_summary_index as usize)
+            .ok_or(ProofError::NotYetFinalized {
+                slot: proof.slot,
+                period: historical_summary_index,
+                latest_summarized_slot: Self::latest_summarized_slot(
+                    historical_summaries.len() as u64
+                ),
+            })?;
 
         if !verify_merkle_proof(
             proof.beacon_block_root,
             &proof.beacon_block_proof,
-            13,
+            HISTORICAL_SUMMARIES_PROOF_DEPTH,
             gen_index as usize,
-            historical_summary,
+            historical_summary.block_summary_root,
         ) {
-            return Err(anyhow!(
-                "Merkle proof validation failed for HistoricalSummariesProof"
-            ));
+            return Err(ProofError::InvalidHistoricalSummariesProof);
         }
 
         Ok(())
     }
 
+    /// Returns the latest slot covered by the given number of historical summaries periods, i.e.
+    /// the last slot of the latest period.
+    pub(crate) fn latest_summarized_slot(periods: u64) -> u64 {
+        (CAPELLA_FORK_EPOCH * SLOTS_PER_EPOCH + periods * EPOCH_SIZE).saturating_sub(1)
+    }
+
+    /// Returns the historical summaries period of the slot, failing for pre-Capella slots.
+    pub(crate) fn historical_summaries_period(slot: u64) -> Result<u64, ProofError> {
+        slot.checked_sub(CAPELLA_FORK_EPOCH * SLOTS_PER_EPOCH)
+            .map(|slots_since_capella| slots_since_capella / EPOCH_SIZE)
+            .ok_or(ProofError::PreCapellaSlot { slot })
+    }
+
     /// Verify that the execution block header is included in the beacon block
     fn verify_beacon_block_proof(
-        header_hash: B256,
+        header: &Header,
         block_body_proof: &[B256],
         block_body_root: B256,
-    ) -> anyhow::Result<()> {
+    ) -> Result<(), ProofError> {
+        let beacon_block_root = Self::reconstruct_beacon_block_root(
+            header.hash_slow(),
+            header.timestamp,
+            block_body_proof.iter().copied(),
+        )?;
+        if beacon_block_root != block_body_root {
+            return Err(ProofError::InvalidBeaconBlockProof);
+        }
+        Ok(())
+    }
+
+    /// Returns the depth and the generalized index of the execution block header in the beacon
+    /// block, for the fork of the mainnet header with the given timestamp.
+    pub(crate) fn execution_block_proof_layout(timestamp: u64) -> (usize, usize) {
+        Self::execution_block_proof_layout_of_fork(HeaderFork::from_timestamp(timestamp))
+    }
+
+    /// Returns the depth and the generalized index of the execution block header in the beacon
+    /// block, for headers of the given fork.
+    pub(crate) fn execution_block_proof_layout_of_fork(fork: HeaderFork) -> (usize, usize) {
         // BeaconBlock level:
         // - 8 as there are 5 fields
         // - 4 as index (pos) of field is 4
         // let gen_index_top_level = (1 * 1 * 8 + 4)
         // BeaconBlockBody level:
-        // - 16 as there are 10 fields
+        // - 16 as there are 10 fields (11 from Capella, 12 from Deneb)
         // - 9 as index (pos) of field is 9
         // let gen_index_mid_level = (gen_index_top_level * 1 * 16 + 9)
-        // ExecutionPayload level:
-        // - 16 as there are 14 fields
-        // - 12 as pos of field is 12
-        // let gen_index = (gen_index_mid_level * 1 * 16 + 12) = 3228
-        let gen_index = 3228;
+        if fork < HeaderFork::Cancun {
+            // ExecutionPayload level:
+            // - 16 as there are 14 fields (15 from Capella)
+            // - 12 as pos of field is 12
+            // let gen_index = (gen_index_mid_level * 1 * 16 + 12) = 3228
+            (EXECUTION_BLOCK_PROOF_DEPTH, EXECUTION_BLOCK_PROOF_GEN_INDEX)
+        } else {
+            // ExecutionPayload level:
+            // - 32 as there are 17 fields
+            // - 12 as pos of field is 12
+            // let gen_index = (gen_index_mid_level * 1 * 32 + 12) = 6444
+            (
+                EXECUTION_BLOCK_PROOF_DEPTH_DENEB,
+                EXECUTION_BLOCK_PROOF_GEN_INDEX_DENEB,
+            )
+        }
+    }
 
-        if !verify_merkle_proof(
+    /// Reconstruct the root of the beacon block that includes the execution block header, from
+    /// the execution block proof.
+    ///
+    /// The layout of the proof depends on the fork of the header, which is determined by its
+    /// `timestamp`.
+    pub(crate) fn reconstruct_beacon_block_root(
+        header_hash: B256,
+        timestamp: u64,
+        block_body_proof: impl IntoIterator<Item = B256>,
+    ) -> Result<B256, ProofError> {
+        Self::reconstruct_beacon_block_root_of_fork(
             header_hash,
+            HeaderFork::from_timestamp(timestamp),
             block_body_proof,
-            block_body_proof.len(),
-            gen_index,
-            block_body_root,
-        ) {
-            return Err(anyhow!(
-                "Merkle proof validation failed for BeaconBlockProof"
-            ));
-        }
-        Ok(())
+        )
+    }
+
+    /// Same as [Self::reconstruct_beacon_block_root], with the layout of the proof of the given
+    /// fork.
+    pub(crate) fn reconstruct_beacon_block_root_of_fork(
+        header_hash: B256,
+        fork: HeaderFork,
+        block_body_proof: impl IntoIterator<Item = B256>,
+    ) -> Result<B256, ProofError> {
+        let (depth, gen_index) = Self::execution_block_proof_layout_of_fork(fork);
+        merkle_root_from_branch_iter(header_hash, block_body_proof, depth, gen_index)
+            .map_err(|length| ProofError::InvalidExecutionBlockProofLength { length })
     }
 }
 
@@ -221,6 +416,7 @@ mod test {
         rlp::Decodable,
     };
     use ethportal_api::{
+        consensus::historical_summaries::HistoricalSummary,
         types::execution::{
             accumulator::EpochAccumulator,
             header_with_proof::{BlockHeaderProof, HeaderWithProof},
@@ -237,7 +433,11 @@ mod test {
     };
 
     use super::*;
-    use crate::constants::DEFAULT_PRE_MERGE_ACC_HASH;
+    use crate::{
+        constants::DEFAULT_PRE_MERGE_ACC_HASH,
+        summaries_verifier::SummariesVerifier,
+        test_utils::{random_historical_roots_proof, random_historical_summaries_proof},
+    };
 
     const SPEC_TESTS_DIR: &str = "tests/mainnet/history";
 
@@ -325,6 +525,91 @@ mod test {
         assert_eq!(encoded_hwp, expected_hwp);
     }
 
+    #[test]
+    fn verify_and_identify_returns_block_number() {
+        let header =
+            Header::decode(&mut hex_decode(HEADER_RLP_15_537_393).unwrap().as_slice()).unwrap();
+        let epoch_acc_bytes = fs::read("./src/assets/epoch_accs/0xe6ebe562c89bc8ecb94dc9b2889a27a816ec05d3d6bd1625acad72227071e721.bin").unwrap();
+        let epoch_acc = EpochAccumulator::from_ssz_bytes(&epoch_acc_bytes).unwrap();
+        let proof = PreMergeAccumulator::construct_proof(&header, &epoch_acc).unwrap();
+        let header_with_proof = HeaderWithProof {
+            header,
+            proof: BlockHeaderProof::HistoricalHashes(proof),
+        };
+
+        let block_number = HeaderValidator::new()
+            .verify_and_identify(&header_with_proof, &HistoricalSummaries::default())
+            .unwrap();
+        assert_eq!(block_number, header_with_proof.header.number);
+        assert_eq!(block_number, 15_537_393);
+    }
+
+    #[test]
+    fn verify_and_identify_verifies_post_capella_header() {
+        // Slot 6209538 belongs to historical summaries period 0
+        let header = generate_random_header(&SHANGHAI_BLOCK_NUMBER);
+        let (proof, block_summary_root) = random_historical_summaries_proof(&header, 6_209_538);
+        let hwp = HeaderWithProof {
+            header,
+            proof: BlockHeaderProof::HistoricalSummaries(proof),
+        };
+        let header_validator = HeaderValidator::new();
+
+        assert_eq!(
+            header_validator.verify_and_identify(&hwp, &HistoricalSummaries::default()),
+            Err(ProofError::NotYetFinalized {
+                slot: 6_209_538,
+                period: 0,
+                latest_summarized_slot: 6_209_535,
+            })
+        );
+        let historical_summaries = vec![HistoricalSummary {
+            block_summary_root: B256::random(),
+            state_summary_root: B256::random(),
+        }]
+        .into();
+        assert_eq!(
+            header_validator.verify_and_identify(&hwp, &historical_summaries),
+            Err(ProofError::InvalidHistoricalSummariesProof)
+        );
+        let historical_summaries = vec![HistoricalSummary {
+            block_summary_root,
+            state_summary_root: B256::random(),
+        }]
+        .into();
+        assert_eq!(
+            header_validator.verify_and_identify(&hwp, &historical_summaries),
+            Ok(SHANGHAI_BLOCK_NUMBER)
+        );
+    }
+
+    #[test]
+    fn verify_post_capella_header_against_summaries_missing_its_period() {
+        // Slot 6238210 belongs to historical summaries period 3
+        let header = generate_random_header(&17_062_257);
+        let (proof, _) = random_historical_summaries_proof(&header, 6_238_210);
+        let historical_summaries = (0..3)
+            .map(|_| HistoricalSummary {
+                block_summary_root: B256::random(),
+                state_summary_root: B256::random(),
+            })
+            .collect::<Vec<_>>()
+            .into();
+
+        assert_eq!(
+            HeaderValidator::new().verify_post_capella_header(
+                &header,
+                &proof,
+                &historical_summaries
+            ),
+            Err(ProofError::NotYetFinalized {
+                slot: 6_238_210,
+                period: 3,
+                latest_summarized_slot: 6_234_111,
+            })
+        );
+    }
+
     #[tokio::test]
     async fn invalidate_invalid_proofs() {
         let header_validator = get_mainnet_header_validator();
@@ -344,7 +629,7 @@ mod test {
     }
 
     #[tokio::test]
-    #[should_panic(expected = "Invalid proof type found for post-merge header.")]
+    #[should_panic(expected = "proof type doesn't match the fork of block 15537395")]
     async fn header_validator_invalidates_post_merge_header_with_accumulator_proof() {
         let header_validator = get_mainnet_header_validator();
         let future_height = MERGE_BLOCK_NUMBER + 1;
@@ -376,29 +661,178 @@ mod test {
         let header_hash = B256::from_str(header_hash).unwrap();
         let historical_roots_block_proof: BlockProofHistoricalRoots =
             serde_yaml::from_value(value).unwrap();
+        let header = read_spec_tests_header(block_number);
+        assert_eq!(header.hash_slow(), header_hash);
 
         header_validator
-            .verify_post_merge_pre_capella_header(
-                block_number,
-                header_hash,
-                &historical_roots_block_proof,
-            )
+            .verify_post_merge_pre_capella_header(&header, &historical_roots_block_proof)
             .unwrap();
 
         // Test for invalid block numbers
-        let validator_result = header_validator.verify_post_merge_pre_capella_header(
-            SHANGHAI_BLOCK_NUMBER,
-            header_hash,
-            &historical_roots_block_proof,
+        for block_number in [SHANGHAI_BLOCK_NUMBER, MERGE_BLOCK_NUMBER - 1] {
+            let invalid_header = Header {
+                number: block_number,
+                ..header.clone()
+            };
+            let validator_result = header_validator.verify_post_merge_pre_capella_header(
+                &invalid_header,
+                &historical_roots_block_proof,
+            );
+            assert_eq!(
+                validator_result,
+                Err(ProofError::UnexpectedProofType { block_number })
+            );
+        }
+    }
+
+    #[test]
+    fn header_validator_validate_terminal_pow_header() {
+        let header =
+            Header::decode(&mut hex_decode(HEADER_RLP_15_537_393).unwrap().as_slice()).unwrap();
+        assert_eq!(header.number, MERGE_BLOCK_NUMBER - 1);
+        let epoch_acc_bytes = fs::read("./src/assets/epoch_accs/0xe6ebe562c89bc8ecb94dc9b2889a27a816ec05d3d6bd1625acad72227071e721.bin").unwrap();
+        let epoch_acc = EpochAccumulator::from_ssz_bytes(&epoch_acc_bytes).unwrap();
+        // The terminal PoW block is the last block of the pre-merge accumulator
+        assert_eq!(
+            header.number,
+            header.number / EPOCH_SIZE * EPOCH_SIZE + epoch_acc.len() as u64 - 1
         );
-        assert!(validator_result.is_err());
+        let header_validator = HeaderValidator::new();
 
-        let validator_result = header_validator.verify_post_merge_pre_capella_header(
-            MERGE_BLOCK_NUMBER,
-            header_hash,
-            &historical_roots_block_proof,
+        let proof = PreMergeAccumulator::construct_proof(&header, &epoch_acc).unwrap();
+        let hwp = HeaderWithProof {
+            header: header.clone(),
+            proof: BlockHeaderProof::HistoricalHashes(proof),
+        };
+        header_validator.validate_header_with_proof(&hwp).unwrap();
+
+        let hwp = HeaderWithProof {
+            header,
+            proof: BlockHeaderProof::HistoricalRoots(BlockProofHistoricalRoots {
+                beacon_block_proof: Default::default(),
+                beacon_block_root: B256::random(),
+                execution_block_proof: Default::default(),
+                slot: 4_700_012,
+            }),
+        };
+        assert_eq!(
+            header_validator
+                .validate_header_with_proof(&hwp)
+                .unwrap_err()
+                .downcast::<ProofError>()
+                .unwrap(),
+            ProofError::UnexpectedProofType {
+                block_number: MERGE_BLOCK_NUMBER - 1
+            }
         );
-        assert!(validator_result.is_err());
+    }
+
+    #[test]
+    fn header_validator_validate_first_pos_header() {
+        let header = generate_random_header(&MERGE_BLOCK_NUMBER);
+        let header_validator = HeaderValidator::new();
+
+        let hwp = HeaderWithProof {
+            header: header.clone(),
+            proof: BlockHeaderProof::HistoricalHashes(Default::default()),
+        };
+        assert_eq!(
+            header_validator
+                .validate_header_with_proof(&hwp)
+                .unwrap_err()
+                .downcast::<ProofError>()
+                .unwrap(),
+            ProofError::UnexpectedProofType {
+                block_number: MERGE_BLOCK_NUMBER
+            }
+        );
+
+        // The proof is checked against historical_roots, after the execution block proof
+        let (proof, _) = random_historical_roots_proof(&header, 4_700_013);
+        let hwp = HeaderWithProof {
+            header,
+            proof: BlockHeaderProof::HistoricalRoots(proof),
+        };
+        assert_eq!(
+            header_validator
+                .validate_header_with_proof(&hwp)
+                .unwrap_err()
+                .to_string(),
+            "Merkle proof validation failed for HistoricalRootsProof"
+        );
+    }
+
+    /// Builds a batch of five headers with proofs, where the second and fourth are invalid.
+    fn batch_with_two_corrupt_items() -> Vec<HeaderWithProof> {
+        let header =
+            Header::decode(&mut hex_decode(HEADER_RLP_15_537_393).unwrap().as_slice()).unwrap();
+        let epoch_acc_bytes = fs::read("./src/assets/epoch_accs/0xe6ebe562c89bc8ecb94dc9b2889a27a816ec05d3d6bd1625acad72227071e721.bin").unwrap();
+        let epoch_acc = EpochAccumulator::from_ssz_bytes(&epoch_acc_bytes).unwrap();
+        let proof = PreMergeAccumulator::construct_proof(&header, &epoch_acc).unwrap();
+        let valid_hwp = HeaderWithProof {
+            header: header.clone(),
+            proof: BlockHeaderProof::HistoricalHashes(proof.clone()),
+        };
+
+        let mut corrupt_proof = proof;
+        corrupt_proof[3] = B256::random();
+        let corrupt_pre_merge_hwp = HeaderWithProof {
+            header,
+            proof: BlockHeaderProof::HistoricalHashes(corrupt_proof),
+        };
+
+        // The historical summaries period of the proof isn't available
+        let post_capella_header = generate_random_header(&SHANGHAI_BLOCK_NUMBER);
+        let (proof, _) = random_historical_summaries_proof(&post_capella_header, 6_209_538);
+        let missing_anchor_hwp = HeaderWithProof {
+            header: post_capella_header,
+            proof: BlockHeaderProof::HistoricalSummaries(proof),
+        };
+
+        vec![
+            valid_hwp.clone(),
+            corrupt_pre_merge_hwp,
+            valid_hwp.clone(),
+            missing_anchor_hwp,
+            valid_hwp,
+        ]
+    }
+
+    #[test]
+    fn header_validator_verify_batch_fail_fast() {
+        let header_validator = HeaderValidator::new();
+        let batch = batch_with_two_corrupt_items();
+
+        let results = header_validator.verify_batch_with(
+            &batch,
+            &HistoricalSummaries::default(),
+            BatchMode::FailFast,
+        );
+        // The items after the first corrupt item are not verified
+        assert_eq!(results.len(), 2);
+        assert!(results[0].is_ok());
+        assert_eq!(results[1], Err(ProofError::InvalidHistoricalHashesProof));
+    }
+
+    #[test]
+    fn header_validator_verify_batch_collect_all() {
+        let header_validator = HeaderValidator::new();
+        let batch = batch_with_two_corrupt_items();
+
+        let results = header_validator.verify_batch_with(
+            &batch,
+            &HistoricalSummaries::default(),
+            BatchMode::CollectAll,
+        );
+        assert_eq!(results.len(), 5);
+        assert_eq!(
+            results.iter().map(Result::is_ok).collect::<Vec<_>>(),
+            vec![true, false, true, false, true]
+        );
+        assert!(matches!(
+            results[3],
+            Err(ProofError::NotYetFinalized { .. })
+        ));
     }
 
     #[rstest]
@@ -423,6 +857,8 @@ mod test {
         let header_hash = B256::from_str(header_hash).unwrap();
         let historical_summaries_block_proof: BlockProofHistoricalSummaries =
             serde_yaml::from_value(value).unwrap();
+        let header = read_spec_tests_header(block_number);
+        assert_eq!(header.hash_slow(), header_hash);
 
         // Load historical summaries from ssz file
         let historical_summaries_bytes =
@@ -435,23 +871,231 @@ mod test {
 
         header_validator
             .verify_post_capella_header(
-                block_number,
-                header_hash,
+                &header,
                 &historical_summaries_block_proof,
-                historical_summaries.clone(),
+                &historical_summaries,
             )
             .unwrap();
 
         // Test for invalid block numbers
+        let invalid_header = Header {
+            number: SHANGHAI_BLOCK_NUMBER - 1,
+            ..header
+        };
         let validator_result = header_validator.verify_post_capella_header(
-            SHANGHAI_BLOCK_NUMBER - 1,
-            header_hash,
+            &invalid_header,
             &historical_summaries_block_proof,
-            historical_summaries,
+            &historical_summaries,
         );
         assert!(validator_result.is_err());
     }
 
+    #[tokio::test]
+    async fn header_validator_post_capella_header_with_missing_historical_summary() {
+        let header_validator = get_mainnet_header_validator();
+        // Block 17062257 is in slot 6238210, which belongs to historical summaries period 3.
+        let block_number = 17062257;
+
+        let file = read_portal_spec_tests_file(PathBuf::from(SPEC_TESTS_DIR).join(format!(
+            "headers_with_proof/block_proofs_capella/beacon_block_proof-{block_number}.yaml"
+        )))
+        .unwrap();
+        let value: serde_yaml::Value = serde_yaml::from_str(&file).unwrap();
+        let header_hash = value
+            .get("execution_block_header")
+            .unwrap()
+            .as_str()
+            .unwrap();
+        let header_hash = B256::from_str(header_hash).unwrap();
+        let historical_summaries_block_proof: BlockProofHistoricalSummaries =
+            serde_yaml::from_value(value).unwrap();
+        let header = read_spec_tests_header(block_number);
+        assert_eq!(header.hash_slow(), header_hash);
+
+        let historical_summaries_bytes =
+            read_portal_spec_tests_file_as_bytes(PathBuf::from(SPEC_TESTS_DIR).join(
+                "headers_with_proof/block_proofs_capella/historical_summaries_at_slot_8953856.ssz",
+            ))
+            .expect("cannot load HistoricalSummaries bytes from test file");
+        let historical_summaries = HistoricalSummaries::from_ssz_bytes(&historical_summaries_bytes)
+            .expect("cannot decode HistoricalSummaries bytes");
+        // Only keep the periods preceding the one needed by the proof
+        let historical_summaries = HistoricalSummaries::from(historical_summaries[..3].to_vec());
+
+        let err = header_validator
+            .verify_post_capella_header(
+                &header,
+                &historical_summaries_block_proof,
+                &historical_summaries,
+            )
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ProofError::NotYetFinalized {
+                slot: 6_238_210,
+                period: 3,
+                latest_summarized_slot: 6_234_111,
+            }
+        );
+    }
+
+    /// Builds a valid post-merge/pre-Capella header with proof, and the historical root that it
+    /// verifies against.
+    fn build_historical_roots_hwp(slot: u64) -> (HeaderWithProof, B256) {
+        let header = generate_random_header(&MERGE_BLOCK_NUMBER);
+        let (proof, historical_root) = random_historical_roots_proof(&header, slot);
+        let hwp = HeaderWithProof {
+            header,
+            proof: BlockHeaderProof::HistoricalRoots(proof),
+        };
+        (hwp, historical_root)
+    }
+
+    /// Fuzzes the slot and the `historical_roots` anchor of a valid proof, which must only verify
+    /// against the genuine historical root.
+    #[quickcheck_macros::quickcheck]
+    fn quickcheck_historical_roots_anchor(slot: u64, length: u16, genuine: bool) -> bool {
+        let slot = slot % (1024 * EPOCH_SIZE);
+        let (hwp, historical_root) = build_historical_roots_hwp(slot);
+        let historical_root_index = (slot / EPOCH_SIZE) as usize;
+        let length = length as usize % 1024;
+        let mut historical_roots: Vec<B256> = (0..length).map(|_| B256::random()).collect();
+        let has_genuine_root = genuine && length > historical_root_index;
+        if has_genuine_root {
+            historical_roots[historical_root_index] = historical_root;
+        }
+        let header_validator = HeaderValidator {
+            pre_merge_acc: PreMergeAccumulator::default(),
+            historical_roots_acc: HistoricalRootsAccumulator {
+                historical_roots: historical_roots.into(),
+            },
+        };
+
+        header_validator.validate_header_with_proof(&hwp).is_ok() == has_genuine_root
+    }
+
+    /// Fuzzes the slot and the `historical_summaries` anchor of a valid proof, which must only
+    /// verify against the genuine historical summary. Pre-Capella slots must fail without
+    /// panicking.
+    #[quickcheck_macros::quickcheck]
+    fn quickcheck_historical_summaries_anchor(slot: u64, length: u8, genuine: bool) -> bool {
+        let capella_slot = CAPELLA_FORK_EPOCH * SLOTS_PER_EPOCH;
+        let slot = slot % (capella_slot + 8 * EPOCH_SIZE);
+        let header = generate_random_header(&SHANGHAI_BLOCK_NUMBER);
+        let (proof, block_summary_root) = random_historical_summaries_proof(&header, slot);
+
+        let length = length as usize % 8;
+        let mut historical_summaries: Vec<HistoricalSummary> = (0..length)
+            .map(|_| HistoricalSummary {
+                block_summary_root: B256::random(),
+                state_summary_root: B256::random(),
+            })
+            .collect();
+        let period = slot
+            .checked_sub(capella_slot)
+            .map(|slots_since_capella| (slots_since_capella / EPOCH_SIZE) as usize);
+        let has_genuine_summary = match period {
+            Some(period) if genuine && length > period => {
+                historical_summaries[period].block_summary_root = block_summary_root;
+                true
+            }
+            _ => false,
+        };
+
+        HeaderValidator::default()
+            .verify_post_capella_header(&header, &proof, &historical_summaries.into())
+            .is_ok()
+            == has_genuine_summary
+    }
+
+    #[test]
+    fn verify_pre_capella_slot_with_historical_summaries_proof() {
+        let slot = CAPELLA_FORK_EPOCH * SLOTS_PER_EPOCH - 1;
+        let header = generate_random_header(&SHANGHAI_BLOCK_NUMBER);
+        let (proof, block_summary_root) = random_historical_summaries_proof(&header, slot);
+        let historical_summaries: HistoricalSummaries = vec![HistoricalSummary {
+            block_summary_root,
+            state_summary_root: B256::random(),
+        }]
+        .into();
+
+        assert_eq!(
+            HeaderValidator::default().verify_post_capella_header(
+                &header,
+                &proof,
+                &historical_summaries
+            ),
+            Err(ProofError::PreCapellaSlot { slot })
+        );
+        assert_eq!(
+            SummariesVerifier::new(&historical_summaries).verify(&proof, &header),
+            Err(ProofError::PreCapellaSlot { slot })
+        );
+
+        // The pre-Capella slot isn't anchored to the first historical summary
+        let hwp = HeaderWithProof {
+            header,
+            proof: BlockHeaderProof::HistoricalSummaries(proof),
+        };
+        let record = HeaderValidator::default().verify_and_record(&hwp, &historical_summaries);
+        assert_eq!(record.anchor_root, B256::ZERO);
+        assert!(!record.outcome);
+    }
+
+    #[test]
+    fn verify_and_record_successful_verification() {
+        // Slot 4700013 belongs to the historical root 573
+        let (hwp, historical_root) = build_historical_roots_hwp(4_700_013);
+        let mut historical_roots = vec![B256::random(); 574];
+        historical_roots[573] = historical_root;
+        let header_validator = HeaderValidator {
+            pre_merge_acc: PreMergeAccumulator::default(),
+            historical_roots_acc: HistoricalRootsAccumulator {
+                historical_roots: historical_roots.into(),
+            },
+        };
+
+        let record = header_validator.verify_and_record(&hwp, &HistoricalSummaries::default());
+        assert_eq!(record.content_id, hwp.canonical_content_id());
+        assert_eq!(record.proof_kind, ProofKind::HistoricalRoots);
+        assert_eq!(record.anchor_root, historical_root);
+        assert!(record.verified_at > 0);
+        assert!(record.outcome);
+
+        let json = serde_json::to_value(&record).unwrap();
+        assert_eq!(json["proof_kind"], "historical_roots");
+        assert_eq!(json["outcome"], true);
+        assert_eq!(
+            json["content_id"],
+            hex_encode(hwp.canonical_content_id()).as_str()
+        );
+        assert_eq!(
+            serde_json::from_value::<VerificationRecord>(json).unwrap(),
+            record
+        );
+    }
+
+    #[test]
+    fn verify_and_record_failed_verification() {
+        let header_validator = HeaderValidator::new();
+        let batch = batch_with_two_corrupt_items();
+
+        let record = header_validator.verify_and_record(&batch[1], &HistoricalSummaries::default());
+        assert_eq!(record.proof_kind, ProofKind::HistoricalHashes);
+        assert_eq!(
+            record.anchor_root,
+            header_validator.pre_merge_acc.historical_epochs
+                [(batch[1].header.number / EPOCH_SIZE) as usize]
+        );
+        assert!(!record.outcome);
+
+        // The historical summaries period isn't available
+        let record = header_validator.verify_and_record(&batch[3], &HistoricalSummaries::default());
+        assert_eq!(record.proof_kind, ProofKind::HistoricalSummaries);
+        assert_eq!(record.anchor_root, B256::ZERO);
+        assert!(!record.outcome);
+    }
+
     //
     // Testing utils
     //
@@ -464,6 +1108,19 @@ mod test {
         header_validator
     }
 
+    /// Reads the header of the header with proof of the spec tests.
+    fn read_spec_tests_header(block_number: u64) -> Header {
+        let file = read_portal_spec_tests_file(
+            PathBuf::from(SPEC_TESTS_DIR).join(format!("headers_with_proof/{block_number}.yaml")),
+        )
+        .unwrap();
+        let yaml: serde_yaml::Value = serde_yaml::from_str(&file).unwrap();
+        let content_value = hex_decode(yaml["content_value"].as_str().unwrap()).unwrap();
+        HeaderWithProof::from_ssz_bytes(&content_value)
+            .unwrap()
+            .header
+    }
+
     pub(crate) fn get_header(number: u64) -> Header {
         let file = fs::read_to_string("./src/assets/header_rlps.json").unwrap();
         let json: Value = serde_json::from_str(&file).unwrap();
@@ -0,0 +1,320 @@
+use alloy::consensus::Header;
+use ethportal_api::{
+    consensus::historical_summaries::{HistoricalSummaries, HistoricalSummary},
+    types::execution::header_with_proof::{
+        BlockHeaderProof, BlockProofHistoricalSummaries, HeaderWithProof, ProofError,
+    },
+};
+use tokio::sync::broadcast;
+use tracing::warn;
+
+use crate::{
+    anchor_provider::AnchorError, constants::EPOCH_SIZE, header_validator::HeaderValidator,
+    summaries_verifier::SummariesVerifier,
+};
+
+/// Capacity of the channel of new historical summaries periods.
+const SUBSCRIPTION_CHANNEL_CAPACITY: usize = 16;
+
+/// What [HistoryAnchors::verify] does with a proof whose slot is newer than the finalized window
+/// of the anchor slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FreshnessPolicy {
+    /// Log a warning, and verify the proof anyway.
+    Warn,
+    /// Fail with [ProofError::NotYetFinalized].
+    Reject,
+}
+
+/// The historical anchors of a long-running node, which grow as new historical summaries periods
+/// are finalized.
+///
+/// Subscribers are notified of every new period, so content that failed verification with
+/// [ProofError::NotYetFinalized] can be verified again once its period is available.
+#[derive(Debug)]
+pub struct HistoryAnchors {
+    verifier: SummariesVerifier,
+    new_periods: broadcast::Sender<u64>,
+    /// The slot that the historical summaries were taken at, with the policy for proofs that are
+    /// too recent for it.
+    anchor_slot: Option<(u64, FreshnessPolicy)>,
+}
+
+impl HistoryAnchors {
+    pub fn new(historical_summaries: &HistoricalSummaries) -> Self {
+        let (new_periods, _) = broadcast::channel(SUBSCRIPTION_CHANNEL_CAPACITY);
+        Self {
+            verifier: SummariesVerifier::new(historical_summaries),
+            new_periods,
+            anchor_slot: None,
+        }
+    }
+
+    /// Sets the slot that the historical summaries were taken at.
+    ///
+    /// Proofs of slots newer than `anchor_slot - SLOTS_PER_HISTORICAL_ROOT` aren't finalized into
+    /// the summaries yet, and could be of a beacon block that was later reorged out, so they are
+    /// handled according to the `policy`.
+    pub fn with_anchor_slot(mut self, anchor_slot: u64, policy: FreshnessPolicy) -> Self {
+        self.anchor_slot = Some((anchor_slot, policy));
+        self
+    }
+
+    /// Returns a channel that receives the historical summaries periods that become available.
+    pub fn subscribe(&self) -> broadcast::Receiver<u64> {
+        self.new_periods.subscribe()
+    }
+
+    /// Adds the historical summary of the next period, and notifies the subscribers.
+    ///
+    /// Pushing the summary of an already known period is a no-op, as long as it matches.
+    pub fn push_summary(
+        &mut self,
+        period: u64,
+        summary: HistoricalSummary,
+    ) -> Result<(), AnchorError> {
+        if let Some(block_summary_root) = self.verifier.block_summary_root(period) {
+            return match block_summary_root == summary.block_summary_root {
+                true => Ok(()),
+                false => Err(AnchorError::ConflictingSummary { period }),
+            };
+        }
+        let expected = self.verifier.periods();
+        if period != expected {
+            return Err(AnchorError::UnexpectedPeriod { period, expected });
+        }
+        self.verifier
+            .push_block_summary_root(summary.block_summary_root);
+        // Sending only fails if there are no subscribers
+        let _ = self.new_periods.send(period);
+        Ok(())
+    }
+
+    pub fn verifier(&self) -> &SummariesVerifier {
+        &self.verifier
+    }
+
+    /// Verifies that the header is part of the canonical chain, see [SummariesVerifier::verify].
+    ///
+    /// If an anchor slot is set, proofs newer than its finalized window are handled according to
+    /// its [FreshnessPolicy].
+    pub fn verify(
+        &self,
+        proof: &BlockProofHistoricalSummaries,
+        header: &Header,
+    ) -> Result<(), ProofError> {
+        if let Some((anchor_slot, policy)) = self.anchor_slot {
+            // SLOTS_PER_HISTORICAL_ROOT is the same as the epoch size of the historical summaries
+            let finalized_slot = anchor_slot.saturating_sub(EPOCH_SIZE);
+            if proof.slot > finalized_slot {
+                match policy {
+                    FreshnessPolicy::Warn => warn!(
+                        slot = proof.slot,
+                        anchor_slot, "Verifying a proof that isn't finalized at the anchor slot"
+                    ),
+                    FreshnessPolicy::Reject => {
+                        return Err(ProofError::NotYetFinalized {
+                            slot: proof.slot,
+                            period: HeaderValidator::historical_summaries_period(proof.slot)?,
+                            latest_summarized_slot: finalized_slot,
+                        })
+                    }
+                }
+            }
+        }
+        self.verifier.verify(proof, header)
+    }
+}
+
+/// Verifies a header with proof against several snapshots of the historical anchors (e.g. of
+/// different finalized checkpoints), which helps during the transition between anchors.
+pub trait VerifyAnyAnchors {
+    /// Returns the index of the first anchors that verify the proof, or the error of the last
+    /// anchors if none of them does.
+    fn verify_any(&self, anchors: &[HistoryAnchors]) -> Result<usize, ProofError>;
+}
+
+impl VerifyAnyAnchors for HeaderWithProof {
+    fn verify_any(&self, anchors: &[HistoryAnchors]) -> Result<usize, ProofError> {
+        let BlockHeaderProof::HistoricalSummaries(proof) = &self.proof else {
+            return Err(ProofError::UnexpectedProofType {
+                block_number: self.header.number,
+            });
+        };
+        let mut last_err = ProofError::NotYetFinalized {
+            slot: proof.slot,
+            period: HeaderValidator::historical_summaries_period(proof.slot)?,
+            latest_summarized_slot: HeaderValidator::latest_summarized_slot(0),
+        };
+        for (index, history_anchors) in anchors.iter().enumerate() {
+            match history_anchors.verify(proof, &self.header) {
+                Ok(()) => return Ok(index),
+                Err(err) => last_err = err,
+            }
+        }
+        Err(last_err)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use alloy::primitives::B256;
+
+    use super::*;
+    use crate::test_utils::random_historical_summaries_proof;
+
+    fn summary(block_summary_root: B256) -> HistoricalSummary {
+        HistoricalSummary {
+            block_summary_root,
+            state_summary_root: B256::random(),
+        }
+    }
+
+    #[test]
+    fn pending_proof_verifies_after_push_summary() {
+        let header = Header {
+            number: 17_062_257,
+            ..Default::default()
+        };
+        // slot 6238210 belongs to historical summaries period 3
+        let slot = 6_238_210;
+        let (proof, block_summary_root) = random_historical_summaries_proof(&header, slot);
+
+        let historical_summaries = (0..3)
+            .map(|_| summary(B256::random()))
+            .collect::<Vec<_>>()
+            .into();
+        let mut anchors = HistoryAnchors::new(&historical_summaries);
+        assert_eq!(
+            anchors.verify(&proof, &header),
+            Err(ProofError::NotYetFinalized {
+                slot,
+                period: 3,
+                latest_summarized_slot: 6_234_111,
+            })
+        );
+
+        let mut new_periods = anchors.subscribe();
+        anchors
+            .push_summary(3, summary(block_summary_root))
+            .unwrap();
+        assert_eq!(new_periods.try_recv().unwrap(), 3);
+        anchors.verify(&proof, &header).unwrap();
+
+        // Pushing the same period again doesn't notify the subscribers
+        anchors
+            .push_summary(3, summary(block_summary_root))
+            .unwrap();
+        assert!(new_periods.try_recv().is_err());
+    }
+
+    #[test]
+    fn verify_proof_inside_and_outside_finalized_window() {
+        let header = Header {
+            number: 17_062_257,
+            ..Default::default()
+        };
+        // slot 6238210 belongs to historical summaries period 3
+        let slot = 6_238_210;
+        let (proof, block_summary_root) = random_historical_summaries_proof(&header, slot);
+        let mut summaries: Vec<_> = (0..3).map(|_| summary(B256::random())).collect();
+        summaries.push(summary(block_summary_root));
+        let historical_summaries = summaries.into();
+
+        // The proof is finalized at the anchor slot
+        let anchors = HistoryAnchors::new(&historical_summaries)
+            .with_anchor_slot(slot + EPOCH_SIZE, FreshnessPolicy::Reject);
+        anchors.verify(&proof, &header).unwrap();
+
+        // The proof is newer than the finalized window of the anchor slot
+        let anchors = HistoryAnchors::new(&historical_summaries)
+            .with_anchor_slot(slot + EPOCH_SIZE - 1, FreshnessPolicy::Reject);
+        assert_eq!(
+            anchors.verify(&proof, &header),
+            Err(ProofError::NotYetFinalized {
+                slot,
+                period: 3,
+                latest_summarized_slot: slot - 1,
+            })
+        );
+        let anchors = HistoryAnchors::new(&historical_summaries)
+            .with_anchor_slot(slot + EPOCH_SIZE - 1, FreshnessPolicy::Warn);
+        anchors.verify(&proof, &header).unwrap();
+    }
+
+    #[test]
+    fn push_summary_rejects_unexpected_period() {
+        let historical_summaries = vec![summary(B256::random())].into();
+        let mut anchors = HistoryAnchors::new(&historical_summaries);
+
+        assert_eq!(
+            anchors.push_summary(2, summary(B256::random())),
+            Err(AnchorError::UnexpectedPeriod {
+                period: 2,
+                expected: 1
+            })
+        );
+        assert_eq!(
+            anchors.push_summary(0, summary(B256::random())),
+            Err(AnchorError::ConflictingSummary { period: 0 })
+        );
+        assert_eq!(anchors.verifier().periods(), 1);
+    }
+
+    #[test]
+    fn verify_any_with_second_anchors() {
+        let header = Header {
+            number: 17_062_257,
+            ..Default::default()
+        };
+        // slot 6238210 belongs to historical summaries period 3
+        let slot = 6_238_210;
+        let (proof, block_summary_root) = random_historical_summaries_proof(&header, slot);
+        let header_with_proof = HeaderWithProof {
+            header,
+            proof: BlockHeaderProof::HistoricalSummaries(proof),
+        };
+
+        let mut summaries: Vec<_> = (0..3).map(|_| summary(B256::random())).collect();
+        let older_anchors = HistoryAnchors::new(&summaries.clone().into());
+        summaries.push(summary(block_summary_root));
+        let newer_anchors = HistoryAnchors::new(&summaries.into());
+
+        assert_eq!(
+            header_with_proof.verify_any(&[older_anchors, newer_anchors]),
+            Ok(1)
+        );
+    }
+
+    #[test]
+    fn verify_any_returns_last_error() {
+        let header = Header {
+            number: 17_062_257,
+            ..Default::default()
+        };
+        let slot = 6_238_210;
+        let (proof, _) = random_historical_summaries_proof(&header, slot);
+        let header_with_proof = HeaderWithProof {
+            header,
+            proof: BlockHeaderProof::HistoricalSummaries(proof),
+        };
+
+        let summaries: Vec<_> = (0..4).map(|_| summary(B256::random())).collect();
+        let older_anchors = HistoryAnchors::new(&summaries[..3].to_vec().into());
+        let newer_anchors = HistoryAnchors::new(&summaries.into());
+
+        assert_eq!(
+            header_with_proof.verify_any(&[older_anchors, newer_anchors]),
+            Err(ProofError::InvalidHistoricalSummariesProof)
+        );
+        assert_eq!(
+            header_with_proof.verify_any(&[]),
+            Err(ProofError::NotYetFinalized {
+                slot,
+                period: 3,
+                latest_summarized_slot: 6_209_535,
+            })
+        );
+    }
+}
@@ -2,12 +2,28 @@
 #![warn(clippy::uninlined_format_args)]
 
 pub mod accumulator;
+pub mod anchor_provider;
+pub mod block_validator;
+pub mod compact_header_with_proof;
 pub mod constants;
+pub mod epoch_header_proof;
+#[cfg(feature = "ethash")]
+pub mod ethash;
+pub mod execution_block_proof;
+pub mod header_rules;
 pub mod header_validator;
 pub mod historical_roots_acc;
+pub mod history_anchors;
 pub mod merkle;
 pub mod oracle;
+pub mod proof_explain;
+pub mod shared_verification_cache;
+pub mod summaries_verifier;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
 pub mod validator;
+pub mod verification_record;
+pub mod verification_requirements;
 
 use rust_embed::RustEmbed;
 
@@ -1,5 +1,5 @@
 use alloy::primitives::B256;
-use ethereum_hashing::{hash, hash32_concat, ZERO_HASHES};
+use ethereum_hashing::{hash32_concat, ZERO_HASHES};
 use lazy_static::lazy_static;
 
 ///
@@ -378,20 +378,38 @@ pub fn verify_merkle_proof(
 pub fn merkle_root_from_branch(leaf: B256, branch: &[B256], depth: usize, index: usize) -> B256 {
     assert_eq!(branch.len(), depth, "proof length should equal depth");
 
-    let mut merkle_root = leaf.as_slice().to_vec();
+    merkle_root_from_branch_iter(leaf, branch.iter().copied(), depth, index)
+        .expect("proof length should equal depth")
+}
 
-    for (i, leaf) in branch.iter().enumerate().take(depth) {
+/// Compute a root hash from a leaf and a Merkle proof, consuming the branch one sibling at a time.
+///
+/// This allows verifying proofs that are decoded incrementally, without buffering the whole
+/// branch. If the branch doesn't have exactly `depth` siblings, its length is returned as the
+/// error.
+pub fn merkle_root_from_branch_iter(
+    leaf: B256,
+    branch: impl IntoIterator<Item = B256>,
+    depth: usize,
+    index: usize,
+) -> Result<B256, usize> {
+    let mut branch = branch.into_iter();
+    let mut merkle_root = leaf;
+
+    for i in 0..depth {
+        let sibling = branch.next().ok_or(i)?;
         let ith_bit = (index >> i) & 0x01;
-        if ith_bit == 1 {
-            merkle_root = hash32_concat(leaf.as_slice(), &merkle_root)[..].to_vec();
+        merkle_root = if ith_bit == 1 {
+            B256::from(hash32_concat(sibling.as_slice(), merkle_root.as_slice()))
         } else {
-            let mut input = merkle_root;
-            input.extend_from_slice(leaf.as_slice());
-            merkle_root = hash(&input);
-        }
+            B256::from(hash32_concat(merkle_root.as_slice(), sibling.as_slice()))
+        };
     }
 
-    B256::from_slice(&merkle_root)
+    match branch.count() {
+        0 => Ok(merkle_root),
+        extra_siblings => Err(depth + extra_siblings),
+    }
 }
 
 impl From<ArithError> for MerkleTreeError {
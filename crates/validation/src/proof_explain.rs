@@ -0,0 +1,112 @@
+use alloy::{consensus::Header, primitives::B256};
+use ethereum_hashing::hash32_concat;
+use ethportal_api::types::execution::header_with_proof::BlockProofHistoricalSummaries;
+
+use crate::{constants::EPOCH_SIZE, header_validator::HeaderValidator};
+
+/// The side of the sibling node, relative to the node being hashed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SiblingSide {
+    Left,
+    Right,
+}
+
+/// A single step of the verification of a Merkle branch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofStep {
+    /// The node being hashed, starting with the leaf of the branch.
+    pub node: B256,
+    /// The sibling node, taken from the proof.
+    pub sibling: B256,
+    /// The side of the sibling node.
+    pub sibling_side: SiblingSide,
+    /// The parent node, which is the hash of the node and its sibling.
+    pub parent: B256,
+}
+
+/// Produces a human-auditable trace of the verification of a proof.
+pub trait ExplainProof {
+    fn explain(&self, header: &Header) -> Vec<ProofStep>;
+}
+
+impl ExplainProof for BlockProofHistoricalSummaries {
+    /// Walks the execution block proof, from the header hash up to the `beacon_block_root`, and
+    /// then the beacon block proof, up to the block summary root of the `historical_summaries`.
+    fn explain(&self, header: &Header) -> Vec<ProofStep> {
+        let (_, gen_index) = HeaderValidator::execution_block_proof_layout(header.timestamp);
+        let mut steps = explain_branch(
+            header.hash_slow(),
+            &self.execution_block_proof,
+            gen_index as u64,
+        );
+        let gen_index = EPOCH_SIZE + self.slot % EPOCH_SIZE;
+        steps.extend(explain_branch(
+            self.beacon_block_root,
+            &self.beacon_block_proof,
+            gen_index,
+        ));
+        steps
+    }
+}
+
+/// Returns the steps of computing the root of the branch, in the same way as
+/// `merkle_root_from_branch`.
+fn explain_branch(leaf: B256, branch: &[B256], gen_index: u64) -> Vec<ProofStep> {
+    let mut node = leaf;
+    branch
+        .iter()
+        .enumerate()
+        .map(|(i, sibling)| {
+            let (sibling_side, parent) = match (gen_index >> i) & 0x01 {
+                1 => (
+                    SiblingSide::Left,
+                    hash32_concat(sibling.as_slice(), node.as_slice()),
+                ),
+                _ => (
+                    SiblingSide::Right,
+                    hash32_concat(node.as_slice(), sibling.as_slice()),
+                ),
+            };
+            let step = ProofStep {
+                node,
+                sibling: *sibling,
+                sibling_side,
+                parent: B256::from(parent),
+            };
+            node = step.parent;
+            step
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::{constants::SHANGHAI_BLOCK_NUMBER, test_utils::random_historical_summaries_proof};
+
+    #[test]
+    fn explain_valid_proof() {
+        let header = Header {
+            number: SHANGHAI_BLOCK_NUMBER,
+            ..Default::default()
+        };
+        let slot = 6_209_538;
+        let (proof, block_summary_root) = random_historical_summaries_proof(&header, slot);
+
+        let steps = proof.explain(&header);
+        assert_eq!(steps.len(), 24);
+        assert_eq!(steps[0].node, header.hash_slow());
+        // The generalized index 3228 is even, so the leaf is the left node
+        assert_eq!(steps[0].sibling_side, SiblingSide::Right);
+        for (step, next_step) in steps[..10].iter().zip(&steps[1..11]) {
+            assert_eq!(step.parent, next_step.node);
+        }
+
+        // The execution block proof ends at the beacon block root
+        assert_eq!(steps[10].parent, proof.beacon_block_root);
+        assert_eq!(steps[11].node, proof.beacon_block_root);
+        // The beacon block proof ends at the block summary root
+        assert_eq!(steps.last().unwrap().parent, block_summary_root);
+    }
+}
@@ -0,0 +1,186 @@
+use std::{
+    future::Future,
+    sync::{Arc, Mutex},
+};
+
+use lru::LruCache;
+use tokio::sync::OnceCell;
+
+/// The default number of verification results kept by [SharedVerificationCache].
+pub const DEFAULT_VERIFICATION_CACHE_CAPACITY: usize = 10_000;
+
+/// The cached result of a verification, which can be shared between tasks.
+pub type SharedVerificationResult = Result<(), Arc<anyhow::Error>>;
+
+/// Caches the results of successful verifications by content id, shared between async tasks.
+///
+/// Verifications are single-flight: the first task to request the verification of a content id
+/// runs it, while the other tasks requesting the same content id wait for its result. If the
+/// running task is cancelled, one of the waiting tasks runs the verification instead.
+///
+/// A failed verification is shared with the tasks waiting for it, but isn't cached, so the next
+/// request of the content id verifies it again. The least recently used results are evicted once
+/// the cache is full.
+#[derive(Debug)]
+pub struct SharedVerificationCache {
+    results: Mutex<LruCache<[u8; 32], Arc<OnceCell<SharedVerificationResult>>>>,
+}
+
+impl Default for SharedVerificationCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_VERIFICATION_CACHE_CAPACITY)
+    }
+}
+
+impl SharedVerificationCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            results: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    /// Returns the result of the verification of the content id, running `verify` if it isn't
+    /// cached nor running in another task.
+    pub async fn get_or_verify<F, Fut>(
+        &self,
+        content_id: [u8; 32],
+        verify: F,
+    ) -> SharedVerificationResult
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = anyhow::Result<()>>,
+    {
+        let cell = {
+            let mut results = self
+                .results
+                .lock()
+                .expect("Verification cache lock poisoned");
+            match results.get(&content_id) {
+                Some(cell) => cell.clone(),
+                None => {
+                    let cell = Arc::new(OnceCell::new());
+                    results.put(content_id, cell.clone());
+                    cell
+                }
+            }
+        };
+        let result = cell
+            .get_or_init(|| async { verify().await.map_err(Arc::new) })
+            .await
+            .clone();
+        if result.is_err() {
+            // Only drop the failed entry, not one inserted since by another request
+            let mut results = self
+                .results
+                .lock()
+                .expect("Verification cache lock poisoned");
+            if results
+                .peek(&content_id)
+                .is_some_and(|cached| Arc::ptr_eq(cached, &cell))
+            {
+                results.pop(&content_id);
+            }
+        }
+        result
+    }
+
+    /// Returns the cached result of the verification of the content id, if it completed
+    /// successfully.
+    pub fn get(&self, content_id: &[u8; 32]) -> Option<SharedVerificationResult> {
+        self.results
+            .lock()
+            .expect("Verification cache lock poisoned")
+            .get(content_id)
+            .and_then(|result| result.get().cloned())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    use anyhow::anyhow;
+
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_verifications_run_once() {
+        let cache = Arc::new(SharedVerificationCache::default());
+        let verifications = Arc::new(AtomicUsize::new(0));
+        let content_id = [1; 32];
+
+        let tasks: Vec<_> = (0..32)
+            .map(|_| {
+                let cache = cache.clone();
+                let verifications = verifications.clone();
+                tokio::spawn(async move {
+                    cache
+                        .get_or_verify(content_id, || async {
+                            verifications.fetch_add(1, Ordering::SeqCst);
+                            tokio::time::sleep(Duration::from_millis(50)).await;
+                            Ok(())
+                        })
+                        .await
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+        assert_eq!(verifications.load(Ordering::SeqCst), 1);
+
+        // Subsequent requests hit the cached result
+        cache
+            .get_or_verify(content_id, || async {
+                verifications.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            })
+            .await
+            .unwrap();
+        assert_eq!(verifications.load(Ordering::SeqCst), 1);
+        assert!(cache.get(&content_id).unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn failed_verification_is_not_cached() {
+        let cache = SharedVerificationCache::default();
+
+        let result = cache
+            .get_or_verify([1; 32], || async { Err(anyhow!("invalid proof")) })
+            .await;
+        assert_eq!(result.unwrap_err().to_string(), "invalid proof");
+        assert!(cache.get(&[1; 32]).is_none());
+
+        // The next request verifies the content id again
+        cache
+            .get_or_verify([1; 32], || async { Ok(()) })
+            .await
+            .unwrap();
+        assert!(cache.get(&[1; 32]).unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn least_recently_used_result_is_evicted() {
+        let cache = SharedVerificationCache::new(2);
+        for content_id in [[1; 32], [2; 32]] {
+            cache
+                .get_or_verify(content_id, || async { Ok(()) })
+                .await
+                .unwrap();
+        }
+
+        // [1; 32] is now more recently used than [2; 32]
+        assert!(cache.get(&[1; 32]).is_some());
+        cache
+            .get_or_verify([3; 32], || async { Ok(()) })
+            .await
+            .unwrap();
+        assert!(cache.get(&[1; 32]).is_some());
+        assert!(cache.get(&[2; 32]).is_none());
+        assert!(cache.get(&[3; 32]).is_some());
+    }
+}
@@ -0,0 +1,800 @@
+use std::time::{Duration, Instant};
+
+use alloy::{consensus::Header, primitives::B256};
+use ethportal_api::{
+    consensus::historical_summaries::HistoricalSummaries,
+    types::execution::{
+        header::{fork_at_timestamp, timestamp_at_slot, KnownNetwork},
+        header_with_proof::{BlockProofHistoricalSummaries, ProofError, VerificationWitness},
+    },
+};
+
+use crate::{
+    constants::{
+        CAPELLA_FORK_EPOCH, EPOCH_SIZE, HISTORICAL_SUMMARIES_PROOF_DEPTH, SHANGHAI_BLOCK_NUMBER,
+        SLOTS_PER_EPOCH,
+    },
+    header_validator::HeaderValidator,
+    merkle::proof::merkle_root_from_branch_iter,
+};
+
+/// The outcome of verifying the chain of proofs of a post-Capella header, with each part of the
+/// chain reported separately.
+///
+/// This allows storing content whose execution block proof is valid, while the
+/// `historical_summaries` period needed to verify the rest of the proof isn't available yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerificationOutcome {
+    /// Whether the header is part of the beacon block.
+    pub execution_branch_ok: bool,
+    /// Whether the beacon block is part of the `historical_summaries`. This is `false` if the
+    /// historical summaries period isn't available, or if the proof doesn't match it.
+    pub anchor_verified: bool,
+}
+
+impl VerificationOutcome {
+    /// Returns whether the whole chain of proofs is verified.
+    pub fn is_verified(&self) -> bool {
+        self.execution_branch_ok && self.anchor_verified
+    }
+}
+
+/// The time spent verifying each branch of the chain of proofs of a post-Capella header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BranchTimings {
+    /// Time spent verifying that the header is part of the beacon block.
+    pub execution_branch: Duration,
+    /// Time spent verifying that the beacon block is part of the `historical_summaries`.
+    pub anchor_branch: Duration,
+}
+
+impl BranchTimings {
+    /// Time spent verifying both branches.
+    pub fn total(&self) -> Duration {
+        self.execution_branch + self.anchor_branch
+    }
+}
+
+/// SummariesVerifier verifies post-Capella headers against the same `historical_summaries`.
+///
+/// The block summary root of every historical summaries period is prepared once, so it can be
+/// reused when verifying many `BlockProofHistoricalSummaries` (e.g. when importing a whole era).
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SummariesVerifier {
+    /// Block summary roots, indexed by historical summaries period.
+    block_summary_roots: Vec<B256>,
+}
+
+impl SummariesVerifier {
+    pub fn new(historical_summaries: &HistoricalSummaries) -> Self {
+        let block_summary_roots = historical_summaries
+            .iter()
+            .map(|historical_summary| historical_summary.block_summary_root)
+            .collect();
+        Self {
+            block_summary_roots,
+        }
+    }
+
+    /// Returns the number of historical summaries periods available.
+    pub fn periods(&self) -> u64 {
+        self.block_summary_roots.len() as u64
+    }
+
+    /// Returns the latest slot covered by the historical summaries periods, i.e. the last slot of
+    /// the latest period.
+    pub fn latest_summarized_slot(&self) -> u64 {
+        HeaderValidator::latest_summarized_slot(self.periods())
+    }
+
+    /// Appends the block summary root of the next historical summaries period.
+    pub fn push_block_summary_root(&mut self, block_summary_root: B256) {
+        self.block_summary_roots.push(block_summary_root);
+    }
+
+    /// Returns the block summary root of the historical summaries period, if available.
+    pub fn block_summary_root(&self, period: u64) -> Option<B256> {
+        self.block_summary_roots.get(period as usize).copied()
+    }
+
+    /// Verifies that the header is part of the canonical chain, using the chain of proofs.
+    ///
+    /// Proofs of beacon blocks that are more recent than the latest historical summaries period
+    /// fail with [ProofError::NotYetFinalized], while proofs that don't match the historical
+    /// summaries fail with [ProofError::InvalidHistoricalSummariesProof].
+    pub fn verify(
+        &self,
+        proof: &BlockProofHistoricalSummaries,
+        header: &Header,
+    ) -> Result<(), ProofError> {
+        self.verify_streaming(
+            header,
+            proof.beacon_block_root,
+            proof.slot,
+            proof.execution_block_proof.iter().copied(),
+            proof.beacon_block_proof.iter().copied(),
+        )
+    }
+
+    /// Verifies the chain of proofs like [Self::verify], consuming the branches of the proof one
+    /// sibling at a time.
+    ///
+    /// This allows a streaming decoder to feed the branches, without materializing the whole
+    /// proof.
+    pub fn verify_streaming(
+        &self,
+        header: &Header,
+        beacon_block_root: B256,
+        slot: u64,
+        execution_block_proof: impl IntoIterator<Item = B256>,
+        beacon_block_proof: impl IntoIterator<Item = B256>,
+    ) -> Result<(), ProofError> {
+        if header.number < SHANGHAI_BLOCK_NUMBER {
+            return Err(ProofError::UnexpectedProofType {
+                block_number: header.number,
+            });
+        }
+
+        Self::verify_execution_branch(header, beacon_block_root, execution_block_proof)?;
+        self.verify_anchor_branch(beacon_block_root, slot, beacon_block_proof)
+    }
+
+    /// Verifies the chain of proofs like [Self::verify], also returning the time spent on each
+    /// branch of the proof.
+    ///
+    /// This helps finding which branch dominates the cost of the verification. If the execution
+    /// branch fails, the anchor branch isn't verified and its time is zero.
+    pub fn verify_profiled(
+        &self,
+        proof: &BlockProofHistoricalSummaries,
+        header: &Header,
+    ) -> (Result<(), ProofError>, BranchTimings) {
+        let mut timings = BranchTimings::default();
+        if header.number < SHANGHAI_BLOCK_NUMBER {
+            let err = ProofError::UnexpectedProofType {
+                block_number: header.number,
+            };
+            return (Err(err), timings);
+        }
+
+        let start = Instant::now();
+        let result = Self::verify_execution_branch(
+            header,
+            proof.beacon_block_root,
+            proof.execution_block_proof.iter().copied(),
+        );
+        timings.execution_branch = start.elapsed();
+        if result.is_err() {
+            return (result, timings);
+        }
+
+        let start = Instant::now();
+        let result = self.verify_anchor_branch(
+            proof.beacon_block_root,
+            proof.slot,
+            proof.beacon_block_proof.iter().copied(),
+        );
+        timings.anchor_branch = start.elapsed();
+        (result, timings)
+    }
+
+    /// Verifies that the header is part of the beacon block.
+    fn verify_execution_branch(
+        header: &Header,
+        beacon_block_root: B256,
+        execution_block_proof: impl IntoIterator<Item = B256>,
+    ) -> Result<(), ProofError> {
+        let reconstructed_beacon_block_root = HeaderValidator::reconstruct_beacon_block_root(
+            header.hash_slow(),
+            header.timestamp,
+            execution_block_proof,
+        )?;
+        if reconstructed_beacon_block_root != beacon_block_root {
+            return Err(ProofError::InvalidBeaconBlockProof);
+        }
+        Ok(())
+    }
+
+    /// Verifies that the beacon block is part of the historical summaries period of its slot.
+    fn verify_anchor_branch(
+        &self,
+        beacon_block_root: B256,
+        slot: u64,
+        beacon_block_proof: impl IntoIterator<Item = B256>,
+    ) -> Result<(), ProofError> {
+        let period = HeaderValidator::historical_summaries_period(slot)?;
+        let block_summary_root =
+            self.block_summary_roots
+                .get(period as usize)
+                .ok_or(ProofError::NotYetFinalized {
+                    slot,
+                    period,
+                    latest_summarized_slot: self.latest_summarized_slot(),
+                })?;
+        let gen_index = EPOCH_SIZE + slot % EPOCH_SIZE;
+        match merkle_root_from_branch_iter(
+            beacon_block_root,
+            beacon_block_proof,
+            HISTORICAL_SUMMARIES_PROOF_DEPTH,
+            gen_index as usize,
+        ) {
+            Ok(root) if root == *block_summary_root => Ok(()),
+            _ => Err(ProofError::InvalidHistoricalSummariesProof),
+        }
+    }
+
+    /// Verifies the chain of proofs like [Self::verify], but reports the outcome of each part of
+    /// the chain separately instead of failing on the first invalid part.
+    ///
+    /// Only malformed proofs (e.g. of the wrong type or length) are returned as errors.
+    pub fn verify_detailed(
+        &self,
+        proof: &BlockProofHistoricalSummaries,
+        header: &Header,
+    ) -> Result<VerificationOutcome, ProofError> {
+        if header.number < SHANGHAI_BLOCK_NUMBER {
+            return Err(ProofError::UnexpectedProofType {
+                block_number: header.number,
+            });
+        }
+
+        let execution_branch_ok = HeaderValidator::reconstruct_beacon_block_root(
+            header.hash_slow(),
+            header.timestamp,
+            proof.execution_block_proof.iter().copied(),
+        )? == proof.beacon_block_root;
+        let anchor_verified = match self.verify_anchor_branch(
+            proof.beacon_block_root,
+            proof.slot,
+            proof.beacon_block_proof.iter().copied(),
+        ) {
+            Ok(()) => true,
+            Err(
+                ProofError::NotYetFinalized { .. } | ProofError::InvalidHistoricalSummariesProof,
+            ) => false,
+            Err(err) => return Err(err),
+        };
+
+        Ok(VerificationOutcome {
+            execution_branch_ok,
+            anchor_verified,
+        })
+    }
+}
+
+/// Checks that the chain of proofs reconstructs the roots recorded in the witness.
+///
+/// This doesn't need the `historical_summaries`, so it only shows that the proof is internally
+/// consistent, the `block_summary_root` of the witness still needs to be checked against them.
+///
+/// The layout of the execution block proof depends on the fork of the slot, so the `network` of
+/// the proof is needed to map the slot to its fork.
+pub fn verify_witness(
+    proof: &BlockProofHistoricalSummaries,
+    witness: &VerificationWitness,
+    network: KnownNetwork,
+) -> Result<(), ProofError> {
+    // The execution payload has the timestamp of its slot
+    let fork = fork_at_timestamp(timestamp_at_slot(proof.slot, network), network);
+    let beacon_block_root = HeaderValidator::reconstruct_beacon_block_root_of_fork(
+        witness.execution_block_hash,
+        fork,
+        proof.execution_block_proof.iter().copied(),
+    )?;
+    if beacon_block_root != witness.beacon_block_root
+        || beacon_block_root != proof.beacon_block_root
+    {
+        return Err(ProofError::InvalidBeaconBlockProof);
+    }
+
+    let gen_index = EPOCH_SIZE + proof.slot % EPOCH_SIZE;
+    match merkle_root_from_branch_iter(
+        beacon_block_root,
+        proof.beacon_block_proof.iter().copied(),
+        HISTORICAL_SUMMARIES_PROOF_DEPTH,
+        gen_index as usize,
+    ) {
+        Ok(root) if root == witness.block_summary_root => Ok(()),
+        _ => Err(ProofError::InvalidHistoricalSummariesProof),
+    }
+}
+
+/// Returns the distinct historical summaries periods needed to verify the headers in the
+/// `start_block..=end_block` range.
+///
+/// Pre-Shanghai blocks are not verified with historical summaries, so they don't require any
+/// period.
+pub fn required_summary_periods(
+    start_block: u64,
+    end_block: u64,
+    block_to_slot: impl Fn(u64) -> u64,
+) -> Vec<usize> {
+    let start_block = start_block.max(SHANGHAI_BLOCK_NUMBER);
+    if start_block > end_block {
+        return vec![];
+    }
+    // Slots increase with block numbers, so every period in between is spanned as well
+    let period = |block_number| {
+        (block_to_slot(block_number).saturating_sub(CAPELLA_FORK_EPOCH * SLOTS_PER_EPOCH)
+            / EPOCH_SIZE) as usize
+    };
+    (period(start_block)..=period(end_block)).collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use ethportal_api::{
+        consensus::historical_summaries::HistoricalSummary,
+        test_utils::{capella_block, deneb_block},
+        types::{
+            consensus::proof::build_merkle_proof_for_index,
+            execution::{
+                block_body::CANCUN_TIMESTAMP,
+                header::{mainnet_slot_from_timestamp, mainnet_timestamp_at_slot},
+                header_with_proof::build_block_proof_historical_summaries_with_witness,
+            },
+        },
+    };
+    use rstest::rstest;
+    use ssz_types::{typenum, FixedVector};
+    use tree_hash::TreeHash;
+    use trin_utils::test_assets::read_test_assets_file;
+
+    use super::*;
+    use crate::test_utils::random_historical_summaries_proof;
+
+    /// Builds historical summaries with the given block summary roots, by period.
+    fn build_historical_summaries(block_summary_roots: Vec<B256>) -> HistoricalSummaries {
+        block_summary_roots
+            .into_iter()
+            .map(|block_summary_root| HistoricalSummary {
+                block_summary_root,
+                state_summary_root: B256::random(),
+            })
+            .collect::<Vec<_>>()
+            .into()
+    }
+
+    fn header(number: u64) -> Header {
+        Header {
+            number,
+            parent_hash: B256::random(),
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case::valid(|_: &mut BlockProofHistoricalSummaries, _: &mut HistoricalSummaries| {})]
+    #[case::invalid_beacon_block_root(
+        |proof: &mut BlockProofHistoricalSummaries, _: &mut HistoricalSummaries| {
+            proof.beacon_block_root = B256::random();
+        }
+    )]
+    #[case::invalid_beacon_block_proof(
+        |proof: &mut BlockProofHistoricalSummaries, _: &mut HistoricalSummaries| {
+            proof.beacon_block_proof[0] = B256::random();
+        }
+    )]
+    #[case::missing_period(
+        |_: &mut BlockProofHistoricalSummaries, summaries: &mut HistoricalSummaries| {
+            *summaries = HistoricalSummaries::from(summaries[..3].to_vec());
+        }
+    )]
+    fn verify_matches_header_validator(
+        #[case] tamper: fn(&mut BlockProofHistoricalSummaries, &mut HistoricalSummaries),
+    ) {
+        // slot 6238210 belongs to historical summaries period 3
+        let header = header(17_062_257);
+        let (mut proof, block_summary_root) = random_historical_summaries_proof(&header, 6_238_210);
+        let mut historical_summaries = build_historical_summaries(vec![
+            B256::random(),
+            B256::random(),
+            B256::random(),
+            block_summary_root,
+        ]);
+        tamper(&mut proof, &mut historical_summaries);
+
+        let expected = HeaderValidator::default().verify_post_capella_header(
+            &header,
+            &proof,
+            &historical_summaries,
+        );
+        let verifier = SummariesVerifier::new(&historical_summaries);
+        assert_eq!(verifier.verify(&proof, &header), expected);
+    }
+
+    #[test]
+    fn verify_proofs_from_multiple_periods() {
+        let capella_slot = CAPELLA_FORK_EPOCH * SLOTS_PER_EPOCH;
+        let (headers, (proofs, block_summary_roots)): (Vec<_>, (Vec<_>, Vec<_>)) = (0..4)
+            .map(|period| {
+                let header = header(SHANGHAI_BLOCK_NUMBER + period * EPOCH_SIZE);
+                let proof =
+                    random_historical_summaries_proof(&header, capella_slot + period * EPOCH_SIZE);
+                (header, proof)
+            })
+            .unzip();
+        let verifier = SummariesVerifier::new(&build_historical_summaries(block_summary_roots));
+
+        for (header, proof) in headers.iter().zip(&proofs) {
+            verifier.verify(proof, header).unwrap();
+        }
+        assert_eq!(
+            verifier.verify(&proofs[0], &headers[1]),
+            Err(ProofError::InvalidBeaconBlockProof)
+        );
+    }
+
+    #[test]
+    fn verify_streaming_from_iterators() {
+        // slot 6238210 belongs to historical summaries period 3
+        let header = header(17_062_257);
+        let (proof, block_summary_root) = random_historical_summaries_proof(&header, 6_238_210);
+        let verifier = SummariesVerifier::new(&build_historical_summaries(vec![
+            B256::random(),
+            B256::random(),
+            B256::random(),
+            block_summary_root,
+        ]));
+
+        // Feed the siblings one at a time, as a streaming decoder would
+        let siblings_read = std::cell::Cell::new(0);
+        let stream = |branch: Vec<B256>| {
+            branch.into_iter().inspect(|_| {
+                siblings_read.set(siblings_read.get() + 1);
+            })
+        };
+        verifier
+            .verify_streaming(
+                &header,
+                proof.beacon_block_root,
+                proof.slot,
+                stream(proof.execution_block_proof.to_vec()),
+                stream(proof.beacon_block_proof.to_vec()),
+            )
+            .unwrap();
+        assert_eq!(siblings_read.get(), 11 + 13);
+
+        let mut execution_block_proof = proof.execution_block_proof.to_vec();
+        execution_block_proof.push(B256::random());
+        assert_eq!(
+            verifier.verify_streaming(
+                &header,
+                proof.beacon_block_root,
+                proof.slot,
+                execution_block_proof,
+                proof.beacon_block_proof.to_vec(),
+            ),
+            Err(ProofError::InvalidExecutionBlockProofLength { length: 12 })
+        );
+        assert_eq!(
+            verifier.verify_streaming(
+                &header,
+                proof.beacon_block_root,
+                proof.slot,
+                proof.execution_block_proof.to_vec(),
+                proof.beacon_block_proof[..12].to_vec(),
+            ),
+            Err(ProofError::InvalidHistoricalSummariesProof)
+        );
+    }
+
+    #[test]
+    fn verify_pre_shanghai_header() {
+        let header = header(SHANGHAI_BLOCK_NUMBER - 1);
+        let (proof, block_summary_root) = random_historical_summaries_proof(&header, 6_238_210);
+        let verifier =
+            SummariesVerifier::new(&build_historical_summaries(vec![block_summary_root]));
+
+        assert_eq!(
+            verifier.verify(&proof, &header),
+            Err(ProofError::UnexpectedProofType {
+                block_number: SHANGHAI_BLOCK_NUMBER - 1
+            })
+        );
+    }
+
+    #[test]
+    fn verify_detailed() {
+        // slot 6238210 belongs to historical summaries period 3
+        let header = header(17_062_257);
+        let (proof, block_summary_root) = random_historical_summaries_proof(&header, 6_238_210);
+        let verifier = SummariesVerifier::new(&build_historical_summaries(vec![
+            B256::random(),
+            B256::random(),
+            B256::random(),
+            block_summary_root,
+        ]));
+
+        let outcome = verifier.verify_detailed(&proof, &header).unwrap();
+        assert_eq!(
+            outcome,
+            VerificationOutcome {
+                execution_branch_ok: true,
+                anchor_verified: true,
+            }
+        );
+        assert!(outcome.is_verified());
+
+        let mut invalid_proof = proof.clone();
+        invalid_proof.execution_block_proof[0] = B256::random();
+        assert_eq!(
+            verifier.verify_detailed(&invalid_proof, &header),
+            Ok(VerificationOutcome {
+                execution_branch_ok: false,
+                anchor_verified: true,
+            })
+        );
+    }
+
+    #[test]
+    fn verify_profiled() {
+        let header = header(17_062_257);
+        let (proof, block_summary_root) = random_historical_summaries_proof(&header, 6_238_210);
+        let verifier = SummariesVerifier::new(&build_historical_summaries(vec![
+            B256::random(),
+            B256::random(),
+            B256::random(),
+            block_summary_root,
+        ]));
+
+        // The timings are too short to be compared to zero, but can't exceed the whole call
+        let start = Instant::now();
+        let (result, timings) = verifier.verify_profiled(&proof, &header);
+        let elapsed = start.elapsed();
+        assert_eq!(result, verifier.verify(&proof, &header));
+        assert_eq!(result, Ok(()));
+        assert!(timings.total() >= timings.execution_branch);
+        assert!(timings.total() >= timings.anchor_branch);
+        assert!(elapsed >= timings.total());
+
+        let mut invalid_proof = proof.clone();
+        invalid_proof.beacon_block_proof[0] = B256::random();
+        let (result, timings) = verifier.verify_profiled(&invalid_proof, &header);
+        assert_eq!(result, verifier.verify(&invalid_proof, &header));
+        assert_eq!(result, Err(ProofError::InvalidHistoricalSummariesProof));
+        assert!(timings.total() >= timings.anchor_branch);
+
+        let mut invalid_proof = proof;
+        invalid_proof.execution_block_proof[0] = B256::random();
+        let (result, timings) = verifier.verify_profiled(&invalid_proof, &header);
+        assert_eq!(result, Err(ProofError::InvalidBeaconBlockProof));
+        assert_eq!(timings.anchor_branch, Duration::ZERO);
+    }
+
+    #[test]
+    fn verify_detailed_with_missing_anchor() {
+        let header = header(17_062_257);
+        let (proof, _) = random_historical_summaries_proof(&header, 6_238_210);
+        let verifier = SummariesVerifier::new(&build_historical_summaries(vec![B256::random()]));
+
+        let outcome = verifier.verify_detailed(&proof, &header).unwrap();
+        assert_eq!(
+            outcome,
+            VerificationOutcome {
+                execution_branch_ok: true,
+                anchor_verified: false,
+            }
+        );
+        assert!(!outcome.is_verified());
+        assert_eq!(
+            verifier.verify(&proof, &header),
+            Err(ProofError::NotYetFinalized {
+                slot: 6_238_210,
+                period: 3,
+                latest_summarized_slot: 6_217_727,
+            })
+        );
+    }
+
+    #[test]
+    fn verify_not_yet_finalized_and_forged_proofs() {
+        // slot 6238210 belongs to historical summaries period 3
+        let header = header(17_062_257);
+        let (proof, block_summary_root) = random_historical_summaries_proof(&header, 6_238_210);
+        let mut verifier = SummariesVerifier::new(&build_historical_summaries(vec![
+            B256::random(),
+            B256::random(),
+            B256::random(),
+        ]));
+        assert_eq!(verifier.latest_summarized_slot(), 6_234_111);
+
+        // The block is more recent than the latest period
+        assert_eq!(
+            verifier.verify(&proof, &header),
+            Err(ProofError::NotYetFinalized {
+                slot: 6_238_210,
+                period: 3,
+                latest_summarized_slot: 6_234_111,
+            })
+        );
+
+        // Forged proof of a block in a summarized period
+        verifier.push_block_summary_root(B256::random());
+        assert_eq!(
+            verifier.verify(&proof, &header),
+            Err(ProofError::InvalidHistoricalSummariesProof)
+        );
+
+        let verifier = SummariesVerifier::new(&build_historical_summaries(vec![
+            B256::random(),
+            B256::random(),
+            B256::random(),
+            block_summary_root,
+        ]));
+        verifier.verify(&proof, &header).unwrap();
+    }
+
+    #[test]
+    fn verify_witness_of_built_proof() {
+        let slot = 6_209_538;
+        let block = capella_block(slot);
+        let beacon_block_root = block.tree_hash_root();
+        let block_roots: FixedVector<B256, typenum::U8192> = (0..8192)
+            .map(|index| match index == slot % EPOCH_SIZE {
+                true => beacon_block_root,
+                false => B256::random(),
+            })
+            .collect::<Vec<_>>()
+            .into();
+
+        let (proof, witness) =
+            build_block_proof_historical_summaries_with_witness(slot, block_roots, block).unwrap();
+        verify_witness(&proof, &witness, KnownNetwork::Mainnet).unwrap();
+
+        // The witness matches a full verification of the proof
+        assert_eq!(witness.beacon_block_root, beacon_block_root);
+        assert_eq!(
+            HeaderValidator::reconstruct_beacon_block_root(
+                witness.execution_block_hash,
+                mainnet_timestamp_at_slot(slot),
+                proof.execution_block_proof.iter().copied(),
+            ),
+            Ok(witness.beacon_block_root)
+        );
+        HeaderValidator::verify_historical_summaries_beacon_block_proof(
+            &proof,
+            &build_historical_summaries(vec![witness.block_summary_root]),
+        )
+        .unwrap();
+
+        let mut invalid_witness = witness.clone();
+        invalid_witness.block_summary_root = B256::random();
+        assert_eq!(
+            verify_witness(&proof, &invalid_witness, KnownNetwork::Mainnet),
+            Err(ProofError::InvalidHistoricalSummariesProof)
+        );
+        let invalid_witness = VerificationWitness {
+            execution_block_hash: B256::random(),
+            ..witness
+        };
+        assert_eq!(
+            verify_witness(&proof, &invalid_witness, KnownNetwork::Mainnet),
+            Err(ProofError::InvalidBeaconBlockProof)
+        );
+    }
+
+    #[test]
+    fn verify_deneb_proof_built_from_beacon_block() {
+        let raw_header = read_test_assets_file("mainnet/block_19433902_value.json").unwrap();
+        let raw_header: serde_json::Value = serde_json::from_str(&raw_header).unwrap();
+        let header: Header = serde_json::from_value(raw_header["result"].clone()).unwrap();
+        let slot = mainnet_slot_from_timestamp(header.timestamp);
+
+        let block = deneb_block(slot, header.hash_slow());
+        let beacon_block_root = block.tree_hash_root();
+        let block_roots: Vec<B256> = (0..EPOCH_SIZE)
+            .map(|index| match index == slot % EPOCH_SIZE {
+                true => beacon_block_root,
+                false => B256::random(),
+            })
+            .collect();
+        let beacon_block_proof = build_merkle_proof_for_index(
+            block_roots.iter().map(|root| root.0).collect(),
+            (slot % EPOCH_SIZE) as usize,
+        );
+        let block_roots: FixedVector<B256, typenum::U8192> = block_roots.into();
+
+        let mut execution_block_proof = block.body.build_execution_block_hash_proof();
+        execution_block_proof.extend(block.build_body_root_proof());
+        let proof = BlockProofHistoricalSummaries {
+            beacon_block_proof: beacon_block_proof.into(),
+            beacon_block_root,
+            execution_block_proof: execution_block_proof.into(),
+            slot,
+        };
+        assert_eq!(proof.execution_block_proof.len(), 12);
+
+        let period = (slot - CAPELLA_FORK_EPOCH * SLOTS_PER_EPOCH) / EPOCH_SIZE;
+        let mut block_summary_roots: Vec<B256> = (0..period).map(|_| B256::random()).collect();
+        block_summary_roots.push(block_roots.tree_hash_root());
+        let historical_summaries = build_historical_summaries(block_summary_roots);
+        let verifier = SummariesVerifier::new(&historical_summaries);
+        verifier.verify(&proof, &header).unwrap();
+        HeaderValidator::default()
+            .verify_post_capella_header(&header, &proof, &historical_summaries)
+            .unwrap();
+
+        // The same proof doesn't verify a pre-Cancun header, which expects 11 hashes
+        let header = Header {
+            timestamp: CANCUN_TIMESTAMP - 1,
+            ..header
+        };
+        assert_eq!(
+            verifier.verify(&proof, &header),
+            Err(ProofError::InvalidExecutionBlockProofLength { length: 12 })
+        );
+    }
+
+    #[test]
+    fn verify_witness_of_network() {
+        // The first slot of Cancun on Sepolia, which is still pre-Cancun on mainnet
+        let slot = 4_243_456;
+        let execution_block_hash = B256::random();
+        let block = deneb_block(slot, execution_block_hash);
+        let beacon_block_root = block.tree_hash_root();
+        let block_roots: Vec<B256> = (0..EPOCH_SIZE)
+            .map(|index| match index == slot % EPOCH_SIZE {
+                true => beacon_block_root,
+                false => B256::random(),
+            })
+            .collect();
+        let beacon_block_proof = build_merkle_proof_for_index(
+            block_roots.iter().map(|root| root.0).collect(),
+            (slot % EPOCH_SIZE) as usize,
+        );
+        let block_roots: FixedVector<B256, typenum::U8192> = block_roots.into();
+
+        let mut execution_block_proof = block.body.build_execution_block_hash_proof();
+        execution_block_proof.extend(block.build_body_root_proof());
+        let proof = BlockProofHistoricalSummaries {
+            beacon_block_proof: beacon_block_proof.into(),
+            beacon_block_root,
+            execution_block_proof: execution_block_proof.into(),
+            slot,
+        };
+        let witness = VerificationWitness {
+            execution_block_hash,
+            beacon_block_root,
+            block_summary_root: block_roots.tree_hash_root(),
+        };
+
+        verify_witness(&proof, &witness, KnownNetwork::Sepolia).unwrap();
+        assert_eq!(
+            verify_witness(&proof, &witness, KnownNetwork::Mainnet),
+            Err(ProofError::InvalidExecutionBlockProofLength { length: 12 })
+        );
+    }
+
+    /// Maps block numbers to slots, as if there were no missed slots since Capella.
+    fn block_to_slot(block_number: u64) -> u64 {
+        CAPELLA_FORK_EPOCH * SLOTS_PER_EPOCH + block_number - SHANGHAI_BLOCK_NUMBER
+    }
+
+    #[rstest]
+    #[case::single_period(SHANGHAI_BLOCK_NUMBER, SHANGHAI_BLOCK_NUMBER + 100, vec![0])]
+    #[case::two_periods(
+        SHANGHAI_BLOCK_NUMBER + EPOCH_SIZE - 1,
+        SHANGHAI_BLOCK_NUMBER + EPOCH_SIZE,
+        vec![0, 1]
+    )]
+    #[case::multiple_periods(
+        SHANGHAI_BLOCK_NUMBER + 3 * EPOCH_SIZE,
+        SHANGHAI_BLOCK_NUMBER + 6 * EPOCH_SIZE + 1,
+        vec![3, 4, 5, 6]
+    )]
+    #[case::pre_shanghai_start(SHANGHAI_BLOCK_NUMBER - 100, SHANGHAI_BLOCK_NUMBER + 100, vec![0])]
+    #[case::pre_shanghai_range(SHANGHAI_BLOCK_NUMBER - 100, SHANGHAI_BLOCK_NUMBER - 1, vec![])]
+    #[case::empty_range(SHANGHAI_BLOCK_NUMBER + 100, SHANGHAI_BLOCK_NUMBER, vec![])]
+    fn summary_periods(
+        #[case] start_block: u64,
+        #[case] end_block: u64,
+        #[case] expected: Vec<usize>,
+    ) {
+        assert_eq!(
+            required_summary_periods(start_block, end_block, block_to_slot),
+            expected
+        );
+    }
+}
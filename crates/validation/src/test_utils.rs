@@ -0,0 +1,70 @@
+use alloy::{consensus::Header, primitives::B256};
+use ethportal_api::types::execution::header_with_proof::{
+    BlockProofHistoricalRoots, BlockProofHistoricalSummaries,
+};
+
+use crate::{
+    constants::{EPOCH_SIZE, HISTORICAL_ROOTS_PROOF_DEPTH, HISTORICAL_SUMMARIES_PROOF_DEPTH},
+    header_validator::HeaderValidator,
+    merkle::proof::merkle_root_from_branch,
+};
+
+/// Builds an execution block proof of the header with random siblings, and the beacon block root
+/// that it reconstructs.
+fn random_execution_block_proof(header: &Header) -> (Vec<B256>, B256) {
+    let (depth, gen_index) = HeaderValidator::execution_block_proof_layout(header.timestamp);
+    let execution_block_proof: Vec<B256> = (0..depth).map(|_| B256::random()).collect();
+    let beacon_block_root =
+        merkle_root_from_branch(header.hash_slow(), &execution_block_proof, depth, gen_index);
+    (execution_block_proof, beacon_block_root)
+}
+
+/// Builds a valid post-merge/pre-Capella proof of the header at the given slot, with random
+/// siblings, and the historical root that it verifies against.
+pub fn random_historical_roots_proof(
+    header: &Header,
+    slot: u64,
+) -> (BlockProofHistoricalRoots, B256) {
+    let (execution_block_proof, beacon_block_root) = random_execution_block_proof(header);
+    let beacon_block_proof: Vec<B256> = (0..HISTORICAL_ROOTS_PROOF_DEPTH)
+        .map(|_| B256::random())
+        .collect();
+    let historical_root = merkle_root_from_branch(
+        beacon_block_root,
+        &beacon_block_proof,
+        HISTORICAL_ROOTS_PROOF_DEPTH,
+        (2 * EPOCH_SIZE + slot % EPOCH_SIZE) as usize,
+    );
+    let proof = BlockProofHistoricalRoots {
+        beacon_block_proof: beacon_block_proof.into(),
+        beacon_block_root,
+        execution_block_proof: execution_block_proof.into(),
+        slot,
+    };
+    (proof, historical_root)
+}
+
+/// Builds a valid post-Capella proof of the header at the given slot, with random siblings, and
+/// the block summary root of the historical summaries period that it verifies against.
+pub fn random_historical_summaries_proof(
+    header: &Header,
+    slot: u64,
+) -> (BlockProofHistoricalSummaries, B256) {
+    let (execution_block_proof, beacon_block_root) = random_execution_block_proof(header);
+    let beacon_block_proof: Vec<B256> = (0..HISTORICAL_SUMMARIES_PROOF_DEPTH)
+        .map(|_| B256::random())
+        .collect();
+    let block_summary_root = merkle_root_from_branch(
+        beacon_block_root,
+        &beacon_block_proof,
+        HISTORICAL_SUMMARIES_PROOF_DEPTH,
+        (EPOCH_SIZE + slot % EPOCH_SIZE) as usize,
+    );
+    let proof = BlockProofHistoricalSummaries {
+        beacon_block_proof: beacon_block_proof.into(),
+        beacon_block_root,
+        execution_block_proof: execution_block_proof.into(),
+        slot,
+    };
+    (proof, block_summary_root)
+}
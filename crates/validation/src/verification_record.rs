@@ -0,0 +1,46 @@
+use alloy::primitives::B256;
+use ethportal_api::types::execution::header_with_proof::BlockHeaderProof;
+use serde::{Deserialize, Serialize};
+
+/// The kind of the proof of a header with proof, i.e. the historical anchors it's verified
+/// against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProofKind {
+    /// Pre-merge proof, verified against the pre-merge accumulator.
+    HistoricalHashes,
+    /// Post-merge/pre-Capella proof, verified against the `historical_roots`.
+    HistoricalRoots,
+    /// Post-Capella proof, verified against the `historical_summaries`.
+    HistoricalSummaries,
+}
+
+impl From<&BlockHeaderProof> for ProofKind {
+    fn from(proof: &BlockHeaderProof) -> Self {
+        match proof {
+            BlockHeaderProof::HistoricalHashes(_) => Self::HistoricalHashes,
+            BlockHeaderProof::HistoricalRoots(_) => Self::HistoricalRoots,
+            BlockHeaderProof::HistoricalSummaries(_) => Self::HistoricalSummaries,
+        }
+    }
+}
+
+/// A record of the verification of a header with proof, for the audit trail of what was
+/// verified.
+///
+/// Records are produced by [crate::header_validator::HeaderValidator::verify_and_record].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerificationRecord {
+    /// The canonical content id of the header, which only depends on the block hash.
+    #[serde(with = "alloy::hex::serde")]
+    pub content_id: [u8; 32],
+    pub proof_kind: ProofKind,
+    /// The root of the historical anchor that the proof was verified against, i.e. the epoch
+    /// accumulator root, the historical root or the block summary root. This is zero if the
+    /// anchor isn't available.
+    pub anchor_root: B256,
+    /// The unix timestamp of the verification, in seconds.
+    pub verified_at: u64,
+    /// Whether the verification succeeded.
+    pub outcome: bool,
+}
@@ -0,0 +1,153 @@
+use std::fmt;
+
+use ethportal_api::types::execution::header_with_proof::{BlockHeaderProof, HeaderWithProof};
+
+use crate::{
+    constants::{MERGE_BLOCK_NUMBER, SHANGHAI_BLOCK_NUMBER},
+    execution_block_proof::CheckInternalIntegrity,
+    header_validator::HeaderValidator,
+    verification_record::ProofKind,
+};
+
+/// What is needed to verify a header with proof, determined without the historical anchors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerificationRequirements {
+    /// The kind of the proof, i.e. the historical anchors it's verified against.
+    pub proof_kind: ProofKind,
+    /// The historical summaries period needed to verify a post-Capella proof, `None` if the slot
+    /// of the proof is before Capella.
+    pub summaries_period: Option<u64>,
+    /// Whether the proof type matches the fork of the header, and the proof that the execution
+    /// block header is part of the beacon block roots to the claimed `beacon_block_root`.
+    pub self_consistent: bool,
+}
+
+impl fmt::Display for VerificationRequirements {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.proof_kind, self.summaries_period) {
+            (ProofKind::HistoricalSummaries, Some(period)) => {
+                write!(f, "needs historical summaries period {period}")?
+            }
+            (ProofKind::HistoricalSummaries, None) => write!(f, "needs historical summaries")?,
+            (ProofKind::HistoricalRoots, _) => write!(f, "needs historical roots")?,
+            _ => write!(f, "needs pre-merge accumulator")?,
+        }
+        if !self.self_consistent {
+            write!(f, " (proof isn't self-consistent)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Lists what is needed to verify a header with proof, as a dry-run before the verification.
+pub trait VerificationChecklist {
+    fn verification_requirements(&self) -> VerificationRequirements;
+}
+
+impl VerificationChecklist for HeaderWithProof {
+    fn verification_requirements(&self) -> VerificationRequirements {
+        let block_number = self.header.number;
+        let (matches_fork, summaries_period) = match &self.proof {
+            BlockHeaderProof::HistoricalHashes(_) => (block_number < MERGE_BLOCK_NUMBER, None),
+            BlockHeaderProof::HistoricalRoots(_) => (
+                (MERGE_BLOCK_NUMBER..SHANGHAI_BLOCK_NUMBER).contains(&block_number),
+                None,
+            ),
+            BlockHeaderProof::HistoricalSummaries(proof) => {
+                // A pre-Capella slot has no historical summaries period
+                let period = HeaderValidator::historical_summaries_period(proof.slot).ok();
+                (
+                    block_number >= SHANGHAI_BLOCK_NUMBER && period.is_some(),
+                    period,
+                )
+            }
+        };
+        VerificationRequirements {
+            proof_kind: ProofKind::from(&self.proof),
+            summaries_period,
+            self_consistent: matches_fork && self.check_internal_integrity().is_ok(),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use alloy::{consensus::Header, primitives::B256};
+
+    use super::*;
+    use crate::{
+        constants::{CAPELLA_FORK_EPOCH, EPOCH_SIZE, SLOTS_PER_EPOCH},
+        test_utils::random_historical_summaries_proof,
+    };
+
+    fn capella_header_with_proof(block_number: u64, slot: u64) -> HeaderWithProof {
+        let header = Header {
+            number: block_number,
+            ..Default::default()
+        };
+        let (proof, _) = random_historical_summaries_proof(&header, slot);
+        HeaderWithProof {
+            header,
+            proof: BlockHeaderProof::HistoricalSummaries(proof),
+        }
+    }
+
+    #[test]
+    fn capella_proof_requirements() {
+        // slot 6238210 belongs to historical summaries period 3
+        let requirements =
+            capella_header_with_proof(17_062_257, 6_238_210).verification_requirements();
+        assert_eq!(
+            requirements,
+            VerificationRequirements {
+                proof_kind: ProofKind::HistoricalSummaries,
+                summaries_period: Some(3),
+                self_consistent: true,
+            }
+        );
+        assert_eq!(
+            requirements.to_string(),
+            "needs historical summaries period 3"
+        );
+
+        // 47 periods after the first slot of Capella
+        let slot = CAPELLA_FORK_EPOCH * SLOTS_PER_EPOCH + 47 * EPOCH_SIZE;
+        let requirements = capella_header_with_proof(17_062_257, slot).verification_requirements();
+        assert_eq!(requirements.summaries_period, Some(47));
+    }
+
+    #[test]
+    fn inconsistent_proof_requirements() {
+        let mut header_with_proof = capella_header_with_proof(17_062_257, 6_238_210);
+        let BlockHeaderProof::HistoricalSummaries(proof) = &mut header_with_proof.proof else {
+            unreachable!()
+        };
+        proof.beacon_block_root = B256::random();
+        let requirements = header_with_proof.verification_requirements();
+        assert!(!requirements.self_consistent);
+        assert_eq!(
+            requirements.to_string(),
+            "needs historical summaries period 3 (proof isn't self-consistent)"
+        );
+
+        // Pre-Shanghai header with a historical summaries proof
+        let mut header_with_proof = capella_header_with_proof(17_062_257, 6_238_210);
+        header_with_proof.header.number = SHANGHAI_BLOCK_NUMBER - 1;
+        assert!(
+            !header_with_proof
+                .verification_requirements()
+                .self_consistent
+        );
+
+        // Historical summaries proof of a pre-Capella slot
+        let slot = CAPELLA_FORK_EPOCH * SLOTS_PER_EPOCH - 1;
+        let requirements = capella_header_with_proof(17_062_257, slot).verification_requirements();
+        assert_eq!(requirements.summaries_period, None);
+        assert!(!requirements.self_consistent);
+        assert_eq!(
+            requirements.to_string(),
+            "needs historical summaries (proof isn't self-consistent)"
+        );
+    }
+}
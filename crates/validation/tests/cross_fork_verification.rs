@@ -0,0 +1,179 @@
+#![allow(clippy::unwrap_used)]
+
+use std::fs;
+
+use alloy::{consensus::Header, hex::FromHex, primitives::B256};
+use ethportal_api::{
+    consensus::historical_summaries::{HistoricalSummaries, HistoricalSummary},
+    test_utils::deneb_block,
+    types::{
+        consensus::proof::build_merkle_proof_for_index,
+        execution::{
+            block_body::CANCUN_TIMESTAMP,
+            header::mainnet_slot_from_timestamp,
+            header_with_proof::{BlockHeaderProof, BlockProofHistoricalSummaries, HeaderWithProof},
+        },
+    },
+};
+use serde::Deserialize;
+use ssz::Decode;
+use ssz_types::{typenum, FixedVector};
+use tree_hash::TreeHash;
+use trin_utils::{
+    submodules::{read_portal_spec_tests_file, read_portal_spec_tests_file_as_bytes},
+    test_assets::read_test_assets_file,
+};
+use trin_validation::{
+    constants::{CAPELLA_FORK_EPOCH, EPOCH_SIZE, SLOTS_PER_EPOCH},
+    header_validator::HeaderValidator,
+    verification_record::ProofKind,
+};
+
+const MANIFEST_PATH: &str = "./tests/assets/cross_fork_manifest.yaml";
+
+/// A mainnet block from the manifest, with the anchors that its proof verifies against.
+#[derive(Debug, Deserialize)]
+struct ManifestEntry {
+    block_number: u64,
+    /// The file with the ssz encoded header with proof, as `content_value`.
+    content_value: String,
+    proof_kind: ProofKind,
+    /// The file with the ssz encoded historical summaries, for the post-Capella blocks.
+    #[serde(default)]
+    historical_summaries: Option<String>,
+}
+
+impl ManifestEntry {
+    fn header_with_proof(&self) -> HeaderWithProof {
+        let file = read_portal_spec_tests_file(&self.content_value).unwrap();
+        let yaml: serde_yaml::Value = serde_yaml::from_str(&file).unwrap();
+        let content_value = yaml["content_value"].as_str().unwrap();
+        HeaderWithProof::from_ssz_bytes(&Vec::from_hex(content_value).unwrap()).unwrap()
+    }
+
+    fn historical_summaries(&self) -> HistoricalSummaries {
+        match &self.historical_summaries {
+            Some(path) => {
+                let bytes = read_portal_spec_tests_file_as_bytes(path).unwrap();
+                HistoricalSummaries::from_ssz_bytes(&bytes).unwrap()
+            }
+            None => HistoricalSummaries::default(),
+        }
+    }
+}
+
+fn read_manifest() -> Vec<ManifestEntry> {
+    let file = fs::read_to_string(MANIFEST_PATH).unwrap();
+    serde_yaml::from_str(&file).unwrap()
+}
+
+#[test]
+fn manifest_spans_all_proof_kinds() {
+    let manifest = read_manifest();
+    for proof_kind in [
+        ProofKind::HistoricalHashes,
+        ProofKind::HistoricalRoots,
+        ProofKind::HistoricalSummaries,
+    ] {
+        assert!(
+            manifest.iter().any(|entry| entry.proof_kind == proof_kind),
+            "manifest has no block verified with {proof_kind:?}"
+        );
+    }
+    for entry in &manifest {
+        assert_eq!(
+            entry.historical_summaries.is_some(),
+            entry.proof_kind == ProofKind::HistoricalSummaries,
+            "block {} has unexpected anchors",
+            entry.block_number
+        );
+    }
+}
+
+#[test]
+fn verify_headers_with_proofs_across_forks() {
+    let header_validator = HeaderValidator::default();
+    for entry in read_manifest() {
+        let hwp = entry.header_with_proof();
+        assert_eq!(hwp.header.number, entry.block_number);
+
+        let record = header_validator.verify_and_record(&hwp, &entry.historical_summaries());
+        assert_eq!(
+            record.proof_kind, entry.proof_kind,
+            "block {} selected unexpected proof kind",
+            entry.block_number
+        );
+        assert!(
+            record.outcome,
+            "block {} failed verification",
+            entry.block_number
+        );
+    }
+}
+
+/// Builds the header with proof of the post-Cancun mainnet block 19433902, from a Deneb beacon
+/// block, together with the historical summaries that it verifies against.
+///
+/// There is no post-Cancun vector in the portal-spec-tests, so unlike the manifest entries, the
+/// proof is generated.
+fn generated_deneb_header_with_proof() -> (HeaderWithProof, HistoricalSummaries) {
+    let file = read_test_assets_file("mainnet/block_19433902_value.json").unwrap();
+    let json: serde_json::Value = serde_json::from_str(&file).unwrap();
+    let header: Header = serde_json::from_value(json["result"].clone()).unwrap();
+    assert!(header.timestamp >= CANCUN_TIMESTAMP);
+    let slot = mainnet_slot_from_timestamp(header.timestamp);
+
+    let block = deneb_block(slot, header.hash_slow());
+    let beacon_block_root = block.tree_hash_root();
+    let block_roots: Vec<B256> = (0..EPOCH_SIZE)
+        .map(|index| match index == slot % EPOCH_SIZE {
+            true => beacon_block_root,
+            false => B256::random(),
+        })
+        .collect();
+    let beacon_block_proof = build_merkle_proof_for_index(
+        block_roots.iter().map(|root| root.0).collect(),
+        (slot % EPOCH_SIZE) as usize,
+    );
+    let mut execution_block_proof = block.body.build_execution_block_hash_proof();
+    execution_block_proof.extend(block.build_body_root_proof());
+
+    let period = (slot - CAPELLA_FORK_EPOCH * SLOTS_PER_EPOCH) / EPOCH_SIZE;
+    let block_summary_root =
+        FixedVector::<B256, typenum::U8192>::from(block_roots).tree_hash_root();
+    let historical_summaries = (0..=period)
+        .map(|index| HistoricalSummary {
+            block_summary_root: match index == period {
+                true => block_summary_root,
+                false => B256::random(),
+            },
+            state_summary_root: B256::random(),
+        })
+        .collect::<Vec<_>>()
+        .into();
+
+    let header_with_proof = HeaderWithProof {
+        header,
+        proof: BlockHeaderProof::HistoricalSummaries(BlockProofHistoricalSummaries {
+            beacon_block_proof: beacon_block_proof.into(),
+            beacon_block_root,
+            execution_block_proof: execution_block_proof.into(),
+            slot,
+        }),
+    };
+    (header_with_proof, historical_summaries)
+}
+
+#[test]
+fn verify_generated_post_cancun_header_with_proof() {
+    let (hwp, historical_summaries) = generated_deneb_header_with_proof();
+    let BlockHeaderProof::HistoricalSummaries(proof) = &hwp.proof else {
+        panic!("post-Cancun header should have a historical summaries proof");
+    };
+    // The execution payload has more than 16 fields from Deneb
+    assert_eq!(proof.execution_block_proof.len(), 12);
+
+    let record = HeaderValidator::default().verify_and_record(&hwp, &historical_summaries);
+    assert_eq!(record.proof_kind, ProofKind::HistoricalSummaries);
+    assert!(record.outcome, "block 19433902 failed verification");
+}